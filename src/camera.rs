@@ -1,4 +1,28 @@
 use crate::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum CameraMode {
+    Centered,
+    Clamped,
+    EdgeScroll,
+}
+
+impl Default for CameraMode {
+    fn default() -> Self {
+        CameraMode::Clamped
+    }
+}
+
+impl CameraMode {
+    pub fn next(self) -> Self {
+        match self {
+            CameraMode::Centered => CameraMode::Clamped,
+            CameraMode::Clamped => CameraMode::EdgeScroll,
+            CameraMode::EdgeScroll => CameraMode::Centered,
+        }
+    }
+}
 
 pub struct Camera {
     pub left_x: i32,
@@ -7,24 +31,132 @@ pub struct Camera {
     pub bottom_y: i32,
     pub width: i32,
     pub height: i32,
+    pub mode: CameraMode,
+    map_width: i32,
+    map_height: i32,
 }
 
 impl Camera {
-    pub fn new(player_position: Point, width: i32, height: i32) -> Self {
-        Self {
-            left_x: player_position.x - width / 2,
-            right_x: player_position.x + width / 2,
-            top_y: player_position.y - height / 2,
-            bottom_y: player_position.y + height / 2,
+    pub fn new(
+        mode: CameraMode,
+        map: &Map,
+        player_position: Point,
+        width: i32,
+        height: i32,
+    ) -> Self {
+        let mut camera = Self {
+            left_x: 0,
+            right_x: 0,
+            top_y: 0,
+            bottom_y: 0,
             width,
             height,
-        }
+            mode,
+            map_width: map.width,
+            map_height: map.height,
+        };
+        camera.left_x = camera.centered(player_position.x, width, map.width);
+        camera.top_y = camera.centered(player_position.y, height, map.height);
+        camera.right_x = camera.left_x + width - 1;
+        camera.bottom_y = camera.top_y + height - 1;
+        camera
     }
 
     pub fn on_player_move(&mut self, player_position: Point) {
-        self.left_x = player_position.x - self.width / 2;
-        self.right_x = player_position.x + self.width / 2;
-        self.top_y = player_position.y - self.height / 2;
-        self.bottom_y = player_position.y + self.height / 2;
+        self.left_x = match self.mode {
+            CameraMode::EdgeScroll => {
+                self.scrolled(self.left_x, player_position.x, self.width, self.map_width)
+            }
+            _ => self.centered(player_position.x, self.width, self.map_width),
+        };
+        self.top_y = match self.mode {
+            CameraMode::EdgeScroll => {
+                self.scrolled(self.top_y, player_position.y, self.height, self.map_height)
+            }
+            _ => self.centered(player_position.y, self.height, self.map_height),
+        };
+        self.right_x = self.left_x + self.width - 1;
+        self.bottom_y = self.top_y + self.height - 1;
+    }
+
+    fn centered(&self, player: i32, view: i32, map_size: i32) -> i32 {
+        let start = player - view / 2;
+        match self.mode {
+            CameraMode::Centered => start,
+            _ => clamp_to_map(start, view, map_size),
+        }
+    }
+
+    fn scrolled(&self, start: i32, player: i32, view: i32, map_size: i32) -> i32 {
+        let dead_zone = view / 4;
+        let start = if player < start + dead_zone {
+            player - dead_zone
+        } else if player > start + view - 1 - dead_zone {
+            player + dead_zone + 1 - view
+        } else {
+            start
+        };
+        clamp_to_map(start, view, map_size)
+    }
+}
+
+fn clamp_to_map(start: i32, view: i32, map_size: i32) -> i32 {
+    let last_start = map_size - view;
+    if last_start < 0 {
+        last_start / 2
+    } else {
+        start.max(0).min(last_start)
     }
 }
+
+#[cfg(test)]
+fn camera_at(mode: CameraMode, map: &Map, player: Point) -> Camera {
+    Camera::new(mode, map, player, 40, 25)
+}
+
+#[test]
+fn centered_camera_ignores_the_map_edges() {
+    let map = Map::new(80, 50);
+    let camera = camera_at(CameraMode::Centered, &map, Point::new(0, 0));
+    assert_eq!((camera.left_x, camera.top_y), (-20, -12));
+    assert_eq!((camera.right_x, camera.bottom_y), (19, 12));
+}
+
+#[test]
+fn clamped_camera_stops_at_every_border() {
+    let map = Map::new(80, 50);
+    let mut camera = camera_at(CameraMode::Clamped, &map, Point::new(0, 0));
+    assert_eq!((camera.left_x, camera.top_y), (0, 0));
+    camera.on_player_move(Point::new(79, 49));
+    assert_eq!((camera.left_x, camera.top_y), (40, 25));
+    assert_eq!((camera.right_x, camera.bottom_y), (79, 49));
+    camera.on_player_move(Point::new(2, 48));
+    assert_eq!((camera.left_x, camera.bottom_y), (0, 49));
+    camera.on_player_move(Point::new(78, 1));
+    assert_eq!((camera.right_x, camera.top_y), (79, 0));
+    camera.on_player_move(Point::new(40, 25));
+    assert_eq!((camera.left_x, camera.top_y), (20, 13));
+}
+
+#[test]
+fn small_maps_are_centered_in_the_view() {
+    let map = Map::new(30, 20);
+    let camera = camera_at(CameraMode::Clamped, &map, Point::new(29, 0));
+    assert_eq!((camera.left_x, camera.right_x), (-5, 34));
+    assert_eq!((camera.top_y, camera.bottom_y), (-2, 22));
+}
+
+#[test]
+fn edge_scroll_only_moves_outside_the_dead_zone() {
+    let map = Map::new(80, 50);
+    let mut camera = camera_at(CameraMode::EdgeScroll, &map, Point::new(40, 25));
+    assert_eq!((camera.left_x, camera.top_y), (20, 13));
+    camera.on_player_move(Point::new(45, 28));
+    assert_eq!((camera.left_x, camera.top_y), (20, 13));
+    camera.on_player_move(Point::new(51, 33));
+    assert_eq!((camera.left_x, camera.top_y), (22, 15));
+    camera.on_player_move(Point::new(79, 49));
+    assert_eq!((camera.right_x, camera.bottom_y), (79, 49));
+    camera.on_player_move(Point::new(0, 0));
+    assert_eq!((camera.left_x, camera.top_y), (0, 0));
+}
@@ -1,6 +1,18 @@
 pub use crate::prelude::*;
+use serde::Deserialize;
 use std::collections::HashSet;
 
+pub const ACTION_COST: i32 = 10;
+pub const NORMAL_SPEED: i32 = 10;
+pub const MAX_SATIETY: i32 = 1000;
+const HUNGRY_THRESHOLD: i32 = 300;
+const WEAK_THRESHOLD: i32 = 150;
+const FAINTING_THRESHOLD: i32 = 50;
+pub const MAX_LANTERN_FUEL: i32 = 1500;
+pub const LOW_LANTERN_FUEL: i32 = 200;
+const LANTERN_RADIUS: i32 = 6;
+const EMBER_RADIUS: i32 = 1;
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct Render {
     pub color: ColorPair,
@@ -33,6 +45,9 @@ pub struct Health {
 #[derive(Clone, PartialEq)]
 pub struct Name(pub String);
 
+#[derive(Clone, PartialEq)]
+pub struct Description(pub String);
+
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub struct WantsToAttack {
     pub attacker: Entity,
@@ -42,6 +57,12 @@ pub struct WantsToAttack {
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub struct ChasingPlayer;
 
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct FollowingPlayer;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Companion;
+
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub struct Item;
 
@@ -62,6 +83,40 @@ pub struct ProvidesHealing {
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct ProvidesDungeonMap {}
 
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ProvidesCharm {}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ProvidesIdentify {}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ProvidesDigging {}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ProvidesBlast {
+    pub radius: i32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ProvidesFire {
+    pub radius: i32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ProvidesGas {
+    pub radius: i32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ProvidesFuel {
+    pub amount: i32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ProvidesNutrition {
+    pub amount: i32,
+}
+
 #[derive(Clone, PartialEq)]
 pub struct Carried {
     pub by: Entity,
@@ -73,12 +128,118 @@ pub struct Damage(pub i32);
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct Weapon;
 
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Equipped;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Quantity(pub i32);
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Charges {
+    pub current: i32,
+    pub max: i32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Key(pub u8);
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Value(pub i32);
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Gold(pub i32);
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DropsGold(pub i32);
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Purse(pub i32);
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Merchant;
+
 #[derive(Clone, Copy, PartialEq)]
 pub struct ActivateItem {
     pub used_by: Entity,
     pub item: Entity,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+pub enum TrapKind {
+    Spikes(i32),
+    Teleport,
+    Alarm,
+    Trapdoor,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Trap {
+    pub kind: TrapKind,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Hidden;
+
+#[derive(Clone, Copy, PartialEq)]
+pub struct TriggerTrap {
+    pub trap: Entity,
+    pub victim: Entity,
+}
+
+#[derive(Clone, PartialEq)]
+pub struct Slain {
+    pub name: String,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub struct WantsToSearch {
+    pub searcher: Entity,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub struct WantsToThrow {
+    pub thrower: Entity,
+    pub item: Entity,
+    pub target: Point,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Energy {
+    pub current: i32,
+    pub speed: i32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Hunger {
+    pub satiety: i32,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Regeneration {
+    pub turns: i32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LightSource {
+    pub radius: i32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Lantern {
+    pub fuel: i32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Torch;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HungerState {
+    Satiated,
+    Hungry,
+    Weak,
+    Fainting,
+}
+
 impl FieldOfView {
     pub fn new(radius: i32) -> Self {
         Self {
@@ -101,3 +262,113 @@ impl FieldOfView {
         }
     }
 }
+
+impl Energy {
+    pub fn new(speed: i32) -> Self {
+        Self {
+            current: speed,
+            speed,
+        }
+    }
+
+    pub fn can_act(&self) -> bool {
+        self.current >= ACTION_COST
+    }
+
+    pub fn spend(&mut self) {
+        self.spend_cost(ACTION_COST);
+    }
+
+    pub fn spend_cost(&mut self, cost: i32) {
+        if self.can_act() {
+            self.current -= cost;
+        }
+    }
+
+    pub fn recharge(&mut self) {
+        self.current += self.speed;
+    }
+}
+
+impl Default for Hunger {
+    fn default() -> Self {
+        Self {
+            satiety: MAX_SATIETY,
+        }
+    }
+}
+
+impl Hunger {
+    pub fn state(&self) -> HungerState {
+        match self.satiety {
+            s if s > HUNGRY_THRESHOLD => HungerState::Satiated,
+            s if s > WEAK_THRESHOLD => HungerState::Hungry,
+            s if s > FAINTING_THRESHOLD => HungerState::Weak,
+            _ => HungerState::Fainting,
+        }
+    }
+
+    pub fn eat(&mut self, amount: i32) {
+        self.satiety = i32::min(MAX_SATIETY, i32::max(0, self.satiety) + amount);
+    }
+}
+
+impl Default for Lantern {
+    fn default() -> Self {
+        Self {
+            fuel: MAX_LANTERN_FUEL,
+        }
+    }
+}
+
+impl Lantern {
+    pub fn light(&self) -> LightSource {
+        LightSource {
+            radius: if self.fuel > 0 {
+                LANTERN_RADIUS
+            } else {
+                EMBER_RADIUS
+            },
+        }
+    }
+
+    pub fn refuel(&mut self, amount: i32) {
+        self.fuel = i32::min(MAX_LANTERN_FUEL, self.fuel + amount);
+    }
+}
+
+impl HungerState {
+    pub fn attack_penalty(self) -> i32 {
+        match self {
+            HungerState::Satiated | HungerState::Hungry => 0,
+            HungerState::Weak | HungerState::Fainting => 1,
+        }
+    }
+
+    pub fn defense_penalty(self) -> i32 {
+        match self {
+            HungerState::Fainting => 1,
+            _ => 0,
+        }
+    }
+}
+
+pub fn item_label(item: &legion::world::EntryRef, identification: &Identification) -> String {
+    let name = item.get_component::<Name>().map_or_else(
+        |_| String::from("???"),
+        |name| String::from(identification.display_name(&name.0)),
+    );
+    let name = match item.get_component::<Quantity>() {
+        Ok(Quantity(n)) if *n > 1 => format!("{} x{}", name, n),
+        _ => name,
+    };
+    let name = match item.get_component::<Charges>() {
+        Ok(charges) => format!("{} ({}/{})", name, charges.current, charges.max),
+        _ => name,
+    };
+    if item.get_component::<Equipped>().is_ok() {
+        format!("{} (equipped)", name)
+    } else {
+        name
+    }
+}
@@ -1,7 +1,10 @@
+use crate::camera::CameraMode;
+use bracket_lib::prelude::RandomNumberGenerator;
 use clap::{crate_authors, crate_description, crate_name, crate_version, App, AppSettings, Arg};
+use serde::{Deserialize, Serialize};
 use std::{fmt, str::FromStr};
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum ArchitectChoice {
     Random,
     Rooms,
@@ -14,14 +17,35 @@ pub struct ArchitectChoiceParseError {
     msg: String,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum ThemeChoice {
     Dungeon,
     Forest,
     Random,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+impl ArchitectChoice {
+    pub fn next(self) -> Self {
+        match self {
+            ArchitectChoice::Random => ArchitectChoice::Rooms,
+            ArchitectChoice::Rooms => ArchitectChoice::Drunkard,
+            ArchitectChoice::Drunkard => ArchitectChoice::CellularAutomata,
+            ArchitectChoice::CellularAutomata => ArchitectChoice::Random,
+        }
+    }
+}
+
+impl ThemeChoice {
+    pub fn next(self) -> Self {
+        match self {
+            ThemeChoice::Random => ThemeChoice::Dungeon,
+            ThemeChoice::Dungeon => ThemeChoice::Forest,
+            ThemeChoice::Forest => ThemeChoice::Random,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct WorldDimensions {
     pub world_width: i32,
     pub world_height: i32,
@@ -33,6 +57,14 @@ impl WorldDimensions {
     pub fn num_tiles(&self) -> usize {
         (self.world_width * self.world_width) as usize
     }
+
+    pub fn resized(&self, world_width: i32, world_height: i32) -> Self {
+        Self {
+            world_width,
+            world_height,
+            ..self.clone()
+        }
+    }
 }
 
 #[derive(Debug, PartialEq)]
@@ -199,11 +231,42 @@ fn test_w_d_too_small() {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     pub architect: ArchitectChoice,
     pub theme: ThemeChoice,
     pub world_dimensions: WorldDimensions,
+    pub hunger: bool,
+    pub seed: Option<u64>,
+    #[serde(default)]
+    pub diagonals: bool,
+    #[serde(default)]
+    pub auto_pickup: bool,
+    #[serde(default)]
+    pub camera: CameraMode,
+}
+
+impl Config {
+    pub fn rng_for_level(&self, level: usize) -> RandomNumberGenerator {
+        match self.seed {
+            Some(seed) => RandomNumberGenerator::seeded(seed.wrapping_add(level as u64)),
+            None => RandomNumberGenerator::new(),
+        }
+    }
+}
+
+#[test]
+fn choices_cycle_back_to_random() {
+    let mut architect = ArchitectChoice::Random;
+    let mut theme = ThemeChoice::Random;
+    for _ in 0..4 {
+        architect = architect.next();
+    }
+    for _ in 0..3 {
+        theme = theme.next();
+    }
+    assert_eq!(architect, ArchitectChoice::Random);
+    assert_eq!(theme, ThemeChoice::Random);
 }
 
 pub fn parse_command_line_args() -> Config {
@@ -235,6 +298,35 @@ pub fn parse_command_line_args() -> Config {
                 .help("size of world expressed as WxH (example: 80x50 is 80 tiles wide by 80 tiles wide")
                 .value_name("architect"),
         )
+        .arg(
+            Arg::with_name("no-hunger")
+                .long("no-hunger")
+                .help("turn off the hunger clock for a more relaxed game"),
+        )
+        .arg(
+            Arg::with_name("diagonals")
+                .long("diagonals")
+                .help("allow moving diagonally, for the player and monsters alike"),
+        )
+        .arg(
+            Arg::with_name("camera")
+                .long("camera")
+                .default_value("Clamped")
+                .possible_values(&["Centered", "Clamped", "EdgeScroll"])
+                .help("how the view follows the player")
+                .value_name("camera"),
+        )
+        .arg(
+            Arg::with_name("auto-pickup")
+                .long("auto-pickup")
+                .help("pick up items found while travelling or exploring"),
+        )
+        .arg(
+            Arg::with_name("seed")
+                .long("seed")
+                .help("seed for the dungeon generator, so the same levels can be replayed")
+                .value_name("seed"),
+        )
         .get_matches();
 
     let arch = matches.value_of("architect");
@@ -257,10 +349,26 @@ pub fn parse_command_line_args() -> Config {
         .unwrap()
         .parse::<WorldDimensions>()
         .unwrap();
+    let hunger = !matches.is_present("no-hunger");
+    let seed = matches.value_of("seed").map(|seed| {
+        seed.parse::<u64>()
+            .expect("The seed must be a whole number.")
+    });
+    let camera = match matches.value_of("camera").unwrap() {
+        "Centered" => CameraMode::Centered,
+        "Clamped" => CameraMode::Clamped,
+        "EdgeScroll" => CameraMode::EdgeScroll,
+        val => panic!(format!("{:?} is not a valid Camera choice.", val)),
+    };
     let config = Config {
         architect,
         world_dimensions,
         theme,
+        hunger,
+        seed,
+        diagonals: matches.is_present("diagonals"),
+        auto_pickup: matches.is_present("auto-pickup"),
+        camera,
     };
     println!("Config = {:?}", config);
     config
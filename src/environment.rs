@@ -0,0 +1,135 @@
+use crate::prelude::*;
+
+pub const FIRE_DURATION: u8 = 3;
+pub const MAX_GAS: u8 = 200;
+pub const HARMFUL_GAS: u8 = 20;
+const FUEL: u8 = 4;
+const SPREAD_CHANCE_PERCENT: i32 = 30;
+const LAVA_IGNITE_CHANCE_PERCENT: i32 = 5;
+const GAS_DECAY: u8 = 3;
+
+pub struct Environment {
+    pub fire: Vec<u8>,
+    pub gas: Vec<u8>,
+    fuel: Vec<u8>,
+}
+
+impl Environment {
+    pub fn new(map: &Map, theme: &Option<Box<dyn MapTheme>>) -> Self {
+        let fuel = map
+            .tiles
+            .iter()
+            .map(|tile| match theme {
+                Some(theme) if theme.is_flammable(*tile) => FUEL,
+                _ => 0,
+            })
+            .collect();
+        Self {
+            fire: vec![0; map.tiles.len()],
+            gas: vec![0; map.tiles.len()],
+            fuel,
+        }
+    }
+
+    pub fn ignite(&mut self, map: &Map, center: Point, radius: i32) {
+        for_each_in_radius(map, center, radius, |idx| self.fire[idx] = FIRE_DURATION);
+    }
+
+    pub fn release_gas(&mut self, map: &Map, center: Point, radius: i32) {
+        for_each_in_radius(map, center, radius, |idx| self.gas[idx] = MAX_GAS);
+    }
+
+    pub fn step(&mut self, map: &Map, rng: &mut RandomNumberGenerator) {
+        self.step_fire(map, rng);
+        self.step_gas(map);
+    }
+
+    fn step_fire(&mut self, map: &Map, rng: &mut RandomNumberGenerator) {
+        let mut next = self.fire.clone();
+        for idx in 0..self.fire.len() {
+            let spreading = self.fire[idx] > 0
+                || (map.tiles[idx] == TileType::Lava
+                    && rng.range(0, 100) < LAVA_IGNITE_CHANCE_PERCENT);
+            if self.fire[idx] > 0 {
+                if self.fuel[idx] > 0 {
+                    self.fuel[idx] -= 1;
+                } else {
+                    next[idx] -= 1;
+                }
+            }
+            if spreading {
+                for neighbor in neighbors(map, idx) {
+                    if self.fire[neighbor] == 0
+                        && self.fuel[neighbor] > 0
+                        && rng.range(0, 100) < SPREAD_CHANCE_PERCENT
+                    {
+                        next[neighbor] = FIRE_DURATION;
+                    }
+                }
+            }
+        }
+        self.fire = next;
+    }
+
+    fn step_gas(&mut self, map: &Map) {
+        let next = (0..self.gas.len())
+            .map(|idx| {
+                if map.is_opaque(idx) {
+                    return 0;
+                }
+                let open_neighbors: Vec<usize> = neighbors(map, idx)
+                    .into_iter()
+                    .filter(|n| !map.is_opaque(*n))
+                    .collect();
+                let total: u32 = open_neighbors
+                    .iter()
+                    .map(|n| u32::from(self.gas[*n]))
+                    .sum::<u32>()
+                    + u32::from(self.gas[idx]);
+                let average = total / (open_neighbors.len() as u32 + 1);
+                (average as u8).saturating_sub(GAS_DECAY)
+            })
+            .collect();
+        self.gas = next;
+    }
+}
+
+fn neighbors(map: &Map, idx: usize) -> Vec<usize> {
+    let pos = map.index_to_point2d(idx);
+    [
+        Point::new(-1, 0),
+        Point::new(1, 0),
+        Point::new(0, -1),
+        Point::new(0, 1),
+    ]
+    .iter()
+    .filter_map(|delta| map.try_idx(pos + *delta))
+    .collect()
+}
+
+fn for_each_in_radius<F>(map: &Map, center: Point, radius: i32, mut f: F)
+where
+    F: FnMut(usize),
+{
+    (-radius..=radius)
+        .cartesian_product(-radius..=radius)
+        .map(|(dy, dx)| center + Point::new(dx, dy))
+        .filter(|pt| DistanceAlg::Pythagoras.distance2d(center, *pt) <= radius as f32 + 0.5)
+        .filter_map(|pt| map.try_idx(pt))
+        .filter(|idx| !map.is_opaque(*idx))
+        .for_each(|idx| f(idx));
+}
+
+#[test]
+fn fire_and_gas_burn_out() {
+    let map = Map::new(10, 10);
+    let mut environment = Environment::new(&map, &None);
+    let mut rng = RandomNumberGenerator::seeded(1);
+    environment.ignite(&map, Point::new(5, 5), 1);
+    environment.release_gas(&map, Point::new(2, 2), 1);
+    for _ in 0..50 {
+        environment.step(&map, &mut rng);
+    }
+    assert!(environment.fire.iter().all(|fire| *fire == 0));
+    assert!(environment.gas.iter().all(|gas| *gas == 0));
+}
@@ -0,0 +1,57 @@
+use crate::prelude::*;
+use serde::Deserialize;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize)]
+pub enum Faction {
+    Player,
+    Monsters,
+    Goblins,
+    Orcs,
+    Giants,
+    Critters,
+    Townsfolk,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Reaction {
+    Hostile,
+    Neutral,
+    Allied,
+}
+
+const REACTIONS: &[(Faction, Faction, Reaction)] = &[
+    (Faction::Player, Faction::Monsters, Reaction::Hostile),
+    (Faction::Player, Faction::Goblins, Reaction::Hostile),
+    (Faction::Player, Faction::Orcs, Reaction::Hostile),
+    (Faction::Player, Faction::Giants, Reaction::Hostile),
+    (Faction::Goblins, Faction::Orcs, Reaction::Hostile),
+    (Faction::Goblins, Faction::Giants, Reaction::Hostile),
+];
+
+impl Faction {
+    pub fn reaction_to(self, other: Faction) -> Reaction {
+        if self == other {
+            return Reaction::Allied;
+        }
+        REACTIONS
+            .iter()
+            .find(|(a, b, _)| (*a == self && *b == other) || (*a == other && *b == self))
+            .map_or(Reaction::Neutral, |(_, _, reaction)| *reaction)
+    }
+
+    pub fn is_hostile_to(self, other: Faction) -> bool {
+        self.reaction_to(other) == Reaction::Hostile
+    }
+}
+
+pub fn reaction_between(ecs: &SubWorld, a: Entity, b: Entity) -> Reaction {
+    let faction_of = |entity: Entity| {
+        ecs.entry_ref(entity)
+            .ok()
+            .and_then(|entry| entry.get_component::<Faction>().ok().copied())
+    };
+    match (faction_of(a), faction_of(b)) {
+        (Some(a), Some(b)) => a.reaction_to(b),
+        _ => Reaction::Neutral,
+    }
+}
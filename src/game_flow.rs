@@ -0,0 +1,163 @@
+use crate::prelude::*;
+
+pub const WORLD_SIZES: &[(i32, i32)] = &[(80, 50), (100, 60), (120, 80)];
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum GameFlow {
+    MainMenu,
+    Options,
+    Playing,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum MainMenuEntry {
+    NewGame,
+    Continue,
+    Options,
+    Quit,
+}
+
+pub const MAIN_MENU: [MainMenuEntry; 4] = [
+    MainMenuEntry::NewGame,
+    MainMenuEntry::Continue,
+    MainMenuEntry::Options,
+    MainMenuEntry::Quit,
+];
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum OptionsEntry {
+    Architect,
+    Theme,
+    Size,
+    Seed,
+    Hunger,
+    Diagonals,
+    AutoPickup,
+    Camera,
+    Back,
+}
+
+pub const OPTIONS_MENU: [OptionsEntry; 9] = [
+    OptionsEntry::Architect,
+    OptionsEntry::Theme,
+    OptionsEntry::Size,
+    OptionsEntry::Seed,
+    OptionsEntry::Hunger,
+    OptionsEntry::Diagonals,
+    OptionsEntry::AutoPickup,
+    OptionsEntry::Camera,
+    OptionsEntry::Back,
+];
+
+#[derive(Clone, Debug, Default)]
+pub struct Menu {
+    pub selected: usize,
+}
+
+impl Menu {
+    pub fn navigate(&mut self, key: VirtualKeyCode, entries: usize) {
+        match key {
+            VirtualKeyCode::Up => self.selected = (self.selected + entries - 1) % entries,
+            VirtualKeyCode::Down => self.selected = (self.selected + 1) % entries,
+            _ => (),
+        }
+    }
+}
+
+impl MainMenuEntry {
+    pub fn label(self) -> &'static str {
+        match self {
+            MainMenuEntry::NewGame => "New Game",
+            MainMenuEntry::Continue => "Continue",
+            MainMenuEntry::Options => "Options",
+            MainMenuEntry::Quit => "Quit",
+        }
+    }
+}
+
+impl OptionsEntry {
+    pub fn label(self, config: &Config) -> String {
+        match self {
+            OptionsEntry::Architect => format!("Architect: {:?}", config.architect),
+            OptionsEntry::Theme => format!("Theme: {:?}", config.theme),
+            OptionsEntry::Size => format!(
+                "Size: {}x{}",
+                config.world_dimensions.world_width, config.world_dimensions.world_height
+            ),
+            OptionsEntry::Seed => match config.seed {
+                Some(seed) => format!("Seed: {}", seed),
+                None => String::from("Seed: Random"),
+            },
+            OptionsEntry::Hunger => format!("Hunger: {}", if config.hunger { "On" } else { "Off" }),
+            OptionsEntry::Diagonals => format!(
+                "Diagonal moves: {}",
+                if config.diagonals { "On" } else { "Off" }
+            ),
+            OptionsEntry::AutoPickup => format!(
+                "Auto pickup: {}",
+                if config.auto_pickup { "On" } else { "Off" }
+            ),
+            OptionsEntry::Camera => format!("Camera: {:?}", config.camera),
+            OptionsEntry::Back => String::from("Back"),
+        }
+    }
+
+    pub fn change(self, config: &mut Config, rng: &mut RandomNumberGenerator) {
+        match self {
+            OptionsEntry::Architect => config.architect = config.architect.next(),
+            OptionsEntry::Theme => config.theme = config.theme.next(),
+            OptionsEntry::Size => {
+                let dimensions = &config.world_dimensions;
+                let current = WORLD_SIZES.iter().position(|(w, h)| {
+                    *w == dimensions.world_width && *h == dimensions.world_height
+                });
+                let (width, height) = match current {
+                    Some(n) => WORLD_SIZES[(n + 1) % WORLD_SIZES.len()],
+                    None => WORLD_SIZES[0],
+                };
+                config.world_dimensions = dimensions.resized(width, height);
+            }
+            OptionsEntry::Seed => {
+                config.seed = match config.seed {
+                    Some(_) => None,
+                    None => Some(rng.next_u64()),
+                }
+            }
+            OptionsEntry::Hunger => config.hunger = !config.hunger,
+            OptionsEntry::Diagonals => config.diagonals = !config.diagonals,
+            OptionsEntry::AutoPickup => config.auto_pickup = !config.auto_pickup,
+            OptionsEntry::Camera => config.camera = config.camera.next(),
+            OptionsEntry::Back => (),
+        }
+    }
+}
+
+pub fn edit_seed(seed: Option<u64>, key: VirtualKeyCode) -> Option<u64> {
+    let digit = match key {
+        VirtualKeyCode::Key0 => Some(0),
+        VirtualKeyCode::Key1 => Some(1),
+        VirtualKeyCode::Key2 => Some(2),
+        VirtualKeyCode::Key3 => Some(3),
+        VirtualKeyCode::Key4 => Some(4),
+        VirtualKeyCode::Key5 => Some(5),
+        VirtualKeyCode::Key6 => Some(6),
+        VirtualKeyCode::Key7 => Some(7),
+        VirtualKeyCode::Key8 => Some(8),
+        VirtualKeyCode::Key9 => Some(9),
+        _ => None,
+    };
+    match (key, digit) {
+        (_, Some(digit)) => Some(seed.unwrap_or(0).saturating_mul(10).saturating_add(digit)),
+        (VirtualKeyCode::Back, _) => seed.map(|seed| seed / 10).filter(|seed| *seed > 0),
+        _ => seed,
+    }
+}
+
+#[test]
+fn typing_digits_edits_the_seed() {
+    let seed = edit_seed(None, VirtualKeyCode::Key4);
+    let seed = edit_seed(seed, VirtualKeyCode::Key2);
+    assert_eq!(seed, Some(42));
+    assert_eq!(edit_seed(seed, VirtualKeyCode::Back), Some(4));
+    assert_eq!(edit_seed(Some(4), VirtualKeyCode::Back), None);
+}
@@ -0,0 +1,92 @@
+use crate::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+pub enum AppearanceKind {
+    Potion,
+    Scroll,
+    Wand,
+}
+
+const MAX_APPEARANCE_ATTEMPTS: usize = 100;
+const POTION_ADJECTIVES: &[&str] = &[
+    "murky", "bubbling", "cloudy", "fizzy", "glowing", "oily", "smoky", "swirling",
+];
+const POTION_COLORS: &[&str] = &[
+    "green", "red", "blue", "amber", "violet", "black", "silver", "pink",
+];
+const SCROLL_SYLLABLES: &[&str] = &[
+    "XY", "ZZY", "KA", "LOR", "FEN", "MU", "THU", "NAX", "REL", "ZO", "QUA", "VIM",
+];
+const WAND_MATERIALS: &[&str] = &[
+    "oak", "bone", "iron", "glass", "ivory", "ebony", "copper", "crystal",
+];
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Identification {
+    appearances: HashMap<String, String>,
+    known: HashSet<String>,
+}
+
+impl Identification {
+    pub fn new(items: &[(String, AppearanceKind)], rng: &mut RandomNumberGenerator) -> Self {
+        let mut used = HashSet::new();
+        let appearances = items
+            .iter()
+            .map(|(name, kind)| {
+                let mut appearance = random_appearance(*kind, rng);
+                for _ in 0..MAX_APPEARANCE_ATTEMPTS {
+                    if !used.contains(&appearance) {
+                        break;
+                    }
+                    appearance = random_appearance(*kind, rng);
+                }
+                used.insert(appearance.clone());
+                (name.clone(), appearance)
+            })
+            .collect();
+        Self {
+            appearances,
+            known: HashSet::new(),
+        }
+    }
+
+    pub fn is_known(&self, name: &str) -> bool {
+        !self.appearances.contains_key(name) || self.known.contains(name)
+    }
+
+    pub fn display_name<'a>(&'a self, name: &'a str) -> &'a str {
+        if self.is_known(name) {
+            name
+        } else {
+            &self.appearances[name]
+        }
+    }
+
+    pub fn identify(&mut self, name: &str) {
+        if self.appearances.contains_key(name) {
+            self.known.insert(String::from(name));
+        }
+    }
+}
+
+fn random_appearance(kind: AppearanceKind, rng: &mut RandomNumberGenerator) -> String {
+    let pick = |words: &[&'static str], rng: &mut RandomNumberGenerator| {
+        *rng.random_slice_entry(words).unwrap()
+    };
+    match kind {
+        AppearanceKind::Potion => format!(
+            "{} {} potion",
+            pick(POTION_ADJECTIVES, rng),
+            pick(POTION_COLORS, rng)
+        ),
+        AppearanceKind::Scroll => format!(
+            "scroll labeled {}{}{}",
+            pick(SCROLL_SYLLABLES, rng),
+            pick(SCROLL_SYLLABLES, rng),
+            pick(SCROLL_SYLLABLES, rng)
+        ),
+        AppearanceKind::Wand => format!("{} wand", pick(WAND_MATERIALS, rng)),
+    }
+}
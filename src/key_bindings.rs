@@ -0,0 +1,358 @@
+use crate::prelude::*;
+use ron::de::from_reader;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::File;
+
+const BINDINGS_FILE: &str = "resources/keybindings.ron";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Action {
+    MoveLeft,
+    MoveRight,
+    MoveUp,
+    MoveDown,
+    MoveUpLeft,
+    MoveUpRight,
+    MoveDownLeft,
+    MoveDownRight,
+    Wait,
+    Rest,
+    PickUp,
+    Search,
+    Inventory,
+    Help,
+    Look,
+    MapOverview,
+    CharacterSheet,
+    GoToStairs,
+    Explore,
+    UseItem(usize),
+}
+
+impl Action {
+    pub const ALL: [Action; 28] = [
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::MoveUp,
+        Action::MoveDown,
+        Action::MoveUpLeft,
+        Action::MoveUpRight,
+        Action::MoveDownLeft,
+        Action::MoveDownRight,
+        Action::Wait,
+        Action::Rest,
+        Action::PickUp,
+        Action::Search,
+        Action::Inventory,
+        Action::Help,
+        Action::Look,
+        Action::MapOverview,
+        Action::CharacterSheet,
+        Action::GoToStairs,
+        Action::Explore,
+        Action::UseItem(0),
+        Action::UseItem(1),
+        Action::UseItem(2),
+        Action::UseItem(3),
+        Action::UseItem(4),
+        Action::UseItem(5),
+        Action::UseItem(6),
+        Action::UseItem(7),
+        Action::UseItem(8),
+    ];
+
+    #[cfg(test)]
+    fn ordinal(self) -> usize {
+        match self {
+            Action::MoveLeft => 0,
+            Action::MoveRight => 1,
+            Action::MoveUp => 2,
+            Action::MoveDown => 3,
+            Action::MoveUpLeft => 4,
+            Action::MoveUpRight => 5,
+            Action::MoveDownLeft => 6,
+            Action::MoveDownRight => 7,
+            Action::Wait => 8,
+            Action::Rest => 9,
+            Action::PickUp => 10,
+            Action::Search => 11,
+            Action::Inventory => 12,
+            Action::Help => 13,
+            Action::Look => 14,
+            Action::MapOverview => 15,
+            Action::CharacterSheet => 16,
+            Action::GoToStairs => 17,
+            Action::Explore => 18,
+            Action::UseItem(n) => 19 + n,
+        }
+    }
+
+    pub fn description(self) -> String {
+        match self {
+            Action::MoveLeft => String::from("Move left"),
+            Action::MoveRight => String::from("Move right"),
+            Action::MoveUp => String::from("Move up"),
+            Action::MoveDown => String::from("Move down"),
+            Action::MoveUpLeft => String::from("Move up and left"),
+            Action::MoveUpRight => String::from("Move up and right"),
+            Action::MoveDownLeft => String::from("Move down and left"),
+            Action::MoveDownRight => String::from("Move down and right"),
+            Action::Wait => String::from("Wait a turn"),
+            Action::Rest => String::from("Rest until healed"),
+            Action::PickUp => String::from("Pick up"),
+            Action::Search => String::from("Search"),
+            Action::Inventory => String::from("Inventory"),
+            Action::Help => String::from("Help"),
+            Action::Look => String::from("Look around"),
+            Action::MapOverview => String::from("Dungeon map"),
+            Action::CharacterSheet => String::from("Character sheet"),
+            Action::GoToStairs => String::from("Travel to the stairs"),
+            Action::Explore => String::from("Explore"),
+            Action::UseItem(n) => format!("Use item {}", n + 1),
+        }
+    }
+
+    pub fn step(self) -> Option<Point> {
+        match self {
+            Action::MoveLeft => Some(Point::new(-1, 0)),
+            Action::MoveRight => Some(Point::new(1, 0)),
+            Action::MoveUp => Some(Point::new(0, -1)),
+            Action::MoveDown => Some(Point::new(0, 1)),
+            Action::MoveUpLeft => Some(Point::new(-1, -1)),
+            Action::MoveUpRight => Some(Point::new(1, -1)),
+            Action::MoveDownLeft => Some(Point::new(-1, 1)),
+            Action::MoveDownRight => Some(Point::new(1, 1)),
+            Action::Wait => Some(Point::zero()),
+            _ => None,
+        }
+    }
+}
+
+const ARROW_KEYS: &[(Action, &str)] = &[
+    (Action::MoveLeft, "Left"),
+    (Action::MoveRight, "Right"),
+    (Action::MoveUp, "Up"),
+    (Action::MoveDown, "Down"),
+];
+
+const VI_KEYS: &[(Action, &str)] = &[
+    (Action::MoveLeft, "H"),
+    (Action::MoveRight, "L"),
+    (Action::MoveUp, "K"),
+    (Action::MoveDown, "J"),
+    (Action::MoveUpLeft, "Y"),
+    (Action::MoveUpRight, "U"),
+    (Action::MoveDownLeft, "B"),
+    (Action::MoveDownRight, "N"),
+];
+
+const NUMPAD_KEYS: &[(Action, &str)] = &[
+    (Action::MoveLeft, "Numpad4"),
+    (Action::MoveRight, "Numpad6"),
+    (Action::MoveUp, "Numpad8"),
+    (Action::MoveDown, "Numpad2"),
+    (Action::MoveUpLeft, "Numpad7"),
+    (Action::MoveUpRight, "Numpad9"),
+    (Action::MoveDownLeft, "Numpad1"),
+    (Action::MoveDownRight, "Numpad3"),
+];
+
+const COMMAND_KEYS: &[(Action, &str)] = &[
+    (Action::Wait, "Space"),
+    (Action::Wait, "Numpad5"),
+    (Action::Rest, "R"),
+    (Action::PickUp, "G"),
+    (Action::Search, "S"),
+    (Action::Inventory, "I"),
+    (Action::Help, "F1"),
+    (Action::Help, "Slash"),
+    (Action::Look, "Semicolon"),
+    (Action::Look, "V"),
+    (Action::MapOverview, "M"),
+    (Action::MapOverview, "Tab"),
+    (Action::CharacterSheet, "C"),
+    (Action::GoToStairs, "X"),
+    (Action::GoToStairs, "Period"),
+    (Action::Explore, "O"),
+    (Action::UseItem(0), "Key1"),
+    (Action::UseItem(1), "Key2"),
+    (Action::UseItem(2), "Key3"),
+    (Action::UseItem(3), "Key4"),
+    (Action::UseItem(4), "Key5"),
+    (Action::UseItem(5), "Key6"),
+    (Action::UseItem(6), "Key7"),
+    (Action::UseItem(7), "Key8"),
+    (Action::UseItem(8), "Key9"),
+];
+
+const KEY_NAMES: &[(&str, VirtualKeyCode)] = &[
+    ("Left", VirtualKeyCode::Left),
+    ("Right", VirtualKeyCode::Right),
+    ("Up", VirtualKeyCode::Up),
+    ("Down", VirtualKeyCode::Down),
+    ("Home", VirtualKeyCode::Home),
+    ("End", VirtualKeyCode::End),
+    ("PageUp", VirtualKeyCode::PageUp),
+    ("PageDown", VirtualKeyCode::PageDown),
+    ("Insert", VirtualKeyCode::Insert),
+    ("Delete", VirtualKeyCode::Delete),
+    ("Space", VirtualKeyCode::Space),
+    ("Return", VirtualKeyCode::Return),
+    ("Tab", VirtualKeyCode::Tab),
+    ("Back", VirtualKeyCode::Back),
+    ("Comma", VirtualKeyCode::Comma),
+    ("Period", VirtualKeyCode::Period),
+    ("Slash", VirtualKeyCode::Slash),
+    ("Semicolon", VirtualKeyCode::Semicolon),
+    ("A", VirtualKeyCode::A),
+    ("B", VirtualKeyCode::B),
+    ("C", VirtualKeyCode::C),
+    ("D", VirtualKeyCode::D),
+    ("E", VirtualKeyCode::E),
+    ("F", VirtualKeyCode::F),
+    ("G", VirtualKeyCode::G),
+    ("H", VirtualKeyCode::H),
+    ("I", VirtualKeyCode::I),
+    ("J", VirtualKeyCode::J),
+    ("K", VirtualKeyCode::K),
+    ("L", VirtualKeyCode::L),
+    ("M", VirtualKeyCode::M),
+    ("N", VirtualKeyCode::N),
+    ("O", VirtualKeyCode::O),
+    ("P", VirtualKeyCode::P),
+    ("Q", VirtualKeyCode::Q),
+    ("R", VirtualKeyCode::R),
+    ("S", VirtualKeyCode::S),
+    ("T", VirtualKeyCode::T),
+    ("U", VirtualKeyCode::U),
+    ("V", VirtualKeyCode::V),
+    ("W", VirtualKeyCode::W),
+    ("X", VirtualKeyCode::X),
+    ("Y", VirtualKeyCode::Y),
+    ("Z", VirtualKeyCode::Z),
+    ("Key0", VirtualKeyCode::Key0),
+    ("Key1", VirtualKeyCode::Key1),
+    ("Key2", VirtualKeyCode::Key2),
+    ("Key3", VirtualKeyCode::Key3),
+    ("Key4", VirtualKeyCode::Key4),
+    ("Key5", VirtualKeyCode::Key5),
+    ("Key6", VirtualKeyCode::Key6),
+    ("Key7", VirtualKeyCode::Key7),
+    ("Key8", VirtualKeyCode::Key8),
+    ("Key9", VirtualKeyCode::Key9),
+    ("Numpad0", VirtualKeyCode::Numpad0),
+    ("Numpad1", VirtualKeyCode::Numpad1),
+    ("Numpad2", VirtualKeyCode::Numpad2),
+    ("Numpad3", VirtualKeyCode::Numpad3),
+    ("Numpad4", VirtualKeyCode::Numpad4),
+    ("Numpad5", VirtualKeyCode::Numpad5),
+    ("Numpad6", VirtualKeyCode::Numpad6),
+    ("Numpad7", VirtualKeyCode::Numpad7),
+    ("Numpad8", VirtualKeyCode::Numpad8),
+    ("Numpad9", VirtualKeyCode::Numpad9),
+    ("F1", VirtualKeyCode::F1),
+    ("F2", VirtualKeyCode::F2),
+    ("F3", VirtualKeyCode::F3),
+    ("F4", VirtualKeyCode::F4),
+    ("F5", VirtualKeyCode::F5),
+    ("F6", VirtualKeyCode::F6),
+    ("F7", VirtualKeyCode::F7),
+    ("F8", VirtualKeyCode::F8),
+    ("F9", VirtualKeyCode::F9),
+    ("F10", VirtualKeyCode::F10),
+    ("F11", VirtualKeyCode::F11),
+    ("F12", VirtualKeyCode::F12),
+];
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct MouseClick(pub Option<Point>);
+
+#[derive(Clone, Debug, Default)]
+pub struct KeyBindings {
+    keys: HashMap<VirtualKeyCode, Action>,
+}
+
+impl KeyBindings {
+    pub fn defaults() -> Self {
+        let mut bindings = Self::default();
+        [ARROW_KEYS, VI_KEYS, NUMPAD_KEYS, COMMAND_KEYS]
+            .iter()
+            .flat_map(|preset| preset.iter())
+            .for_each(|(action, name)| bindings.bind(*action, name));
+        bindings
+    }
+
+    pub fn load() -> Self {
+        let mut bindings = Self::defaults();
+        match File::open(BINDINGS_FILE) {
+            Ok(file) => match from_reader::<_, HashMap<Action, Vec<String>>>(file) {
+                Ok(overrides) => {
+                    overrides.iter().for_each(|(action, names)| {
+                        bindings.keys.retain(|_, bound| bound != action);
+                        names.iter().for_each(|name| bindings.bind(*action, name));
+                    });
+                    Action::ALL
+                        .iter()
+                        .filter(|action| bindings.key_names(**action).is_empty())
+                        .for_each(|action| {
+                            println!(
+                                "Warning: {} leaves no key for {}",
+                                BINDINGS_FILE,
+                                action.description()
+                            )
+                        });
+                }
+                Err(err) => println!("Warning: ignoring {}: {}", BINDINGS_FILE, err),
+            },
+            Err(_) => println!("Warning: no {}, using the default keys", BINDINGS_FILE),
+        }
+        bindings
+    }
+
+    fn bind(&mut self, action: Action, name: &str) {
+        match KEY_NAMES.iter().find(|(key_name, _)| *key_name == name) {
+            Some((_, key)) => {
+                self.keys.insert(*key, action);
+            }
+            None => println!("Warning: we don't know a key called {}", name),
+        }
+    }
+
+    pub fn action(&self, key: VirtualKeyCode) -> Option<Action> {
+        self.keys.get(&key).copied()
+    }
+
+    pub fn key_names(&self, action: Action) -> Vec<&'static str> {
+        KEY_NAMES
+            .iter()
+            .filter(|(_, key)| self.action(*key) == Some(action))
+            .map(|(name, _)| *name)
+            .collect()
+    }
+
+    pub fn describe(&self, action: Action) -> String {
+        self.key_names(action).join(" / ")
+    }
+}
+
+#[test]
+fn all_actions_are_listed_in_order() {
+    Action::ALL.iter().enumerate().for_each(|(n, action)| {
+        assert_eq!(action.ordinal(), n, "{:?} is out of place", action);
+    });
+    assert_eq!(Action::ALL.len(), Action::UseItem(8).ordinal() + 1);
+}
+
+#[test]
+fn every_action_has_a_binding() {
+    for bindings in [KeyBindings::defaults(), KeyBindings::load()].iter() {
+        Action::ALL.iter().for_each(|action| {
+            assert!(
+                !bindings.key_names(*action).is_empty(),
+                "{:?} has no key",
+                action
+            );
+        });
+    }
+}
@@ -0,0 +1,62 @@
+use crate::prelude::*;
+
+pub const MIN_VISIBLE_LIGHT: f32 = 0.1;
+const LAVA_GLOW: f32 = 0.6;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LightMap {
+    levels: Vec<f32>,
+}
+
+impl LightMap {
+    pub fn new(map: &Map) -> Self {
+        Self {
+            levels: vec![0.0; map.tiles.len()],
+        }
+    }
+
+    pub fn compute(map: &Map, sources: &[(Point, i32)]) -> Self {
+        let mut levels: Vec<f32> = map
+            .tiles
+            .iter()
+            .map(|tile| match tile {
+                TileType::Lava => LAVA_GLOW,
+                _ => 0.0,
+            })
+            .collect();
+        sources.iter().for_each(|(source, radius)| {
+            field_of_view_set(*source, *radius, map)
+                .iter()
+                .filter_map(|pt| map.try_idx(*pt).map(|idx| (idx, *pt)))
+                .for_each(|(idx, pt)| {
+                    let distance = DistanceAlg::Pythagoras.distance2d(*source, pt);
+                    let light = 1.0 - distance / (*radius as f32 + 1.0);
+                    levels[idx] = f32::min(1.0, levels[idx] + f32::max(0.0, light));
+                });
+        });
+        Self { levels }
+    }
+
+    pub fn level(&self, idx: usize) -> f32 {
+        self.levels.get(idx).copied().unwrap_or(0.0)
+    }
+
+    pub fn is_lit(&self, idx: usize) -> bool {
+        self.level(idx) >= MIN_VISIBLE_LIGHT
+    }
+}
+
+#[test]
+fn light_fades_with_distance_and_stops_at_walls() {
+    let mut map = Map::new(10, 3);
+    (0..3).for_each(|y| {
+        let idx = map.point2d_to_index(Point::new(5, y));
+        map.tiles[idx] = TileType::Wall;
+    });
+    let light_map = LightMap::compute(&map, &[(Point::new(2, 1), 4)]);
+    let level = |x| light_map.level(map.point2d_to_index(Point::new(x, 1)));
+    assert!(level(2) > level(3));
+    assert!(level(3) > level(4));
+    assert!(light_map.is_lit(map.point2d_to_index(Point::new(5, 1))));
+    assert!(!light_map.is_lit(map.point2d_to_index(Point::new(6, 1))));
+}
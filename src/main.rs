@@ -3,20 +3,44 @@
 mod camera;
 mod components;
 mod config;
+mod environment;
+mod faction;
+mod game_flow;
+mod identification;
+mod key_bindings;
+mod lighting;
 mod map;
 mod map_builder;
+mod message_log;
+mod minimap;
+mod save;
+mod screens;
 mod spawner;
+mod statistics;
 mod systems;
+mod travel;
 mod turn_state;
 
 pub mod prelude {
     pub use crate::camera::*;
     pub use crate::components::*;
     pub use crate::config::*;
+    pub use crate::environment::*;
+    pub use crate::faction::*;
+    pub use crate::game_flow::*;
+    pub use crate::identification::*;
+    pub use crate::key_bindings::*;
+    pub use crate::lighting::*;
     pub use crate::map::*;
     pub use crate::map_builder::*;
+    pub use crate::message_log::*;
+    pub use crate::minimap::*;
+    pub use crate::save::*;
+    pub use crate::screens::*;
     pub use crate::spawner::*;
+    pub use crate::statistics::*;
     pub use crate::systems::*;
+    pub use crate::travel::*;
     pub use crate::turn_state::*;
     pub use bracket_lib::prelude::*;
     pub use itertools::*;
@@ -60,6 +84,7 @@ pub mod prelude {
     };
 
     pub const DISTANCE_MAX_DEPTH: f32 = 1024.0;
+    pub const UNREACHABLE: f32 = std::f32::MAX;
     pub const RENDER_LAYERS: [LayerDef; 3] = [BACKGROUND_LAYER, ENTITY_LAYER, HUD_LAYER];
 
     pub fn fifty_fifty(rng: &mut RandomNumberGenerator) -> bool {
@@ -69,13 +94,25 @@ pub mod prelude {
 
 use prelude::*;
 
+const COMPANION_FOLLOW_DISTANCE: f32 = 3.0;
+const AMULET_LEVEL: usize = 2;
+
 struct State {
     ecs: World,
     resources: Resources,
     input_systems: Schedule,
     player_systems: Schedule,
     monster_systems: Schedule,
+    inventory_systems: Schedule,
+    trade_systems: Schedule,
+    help_systems: Schedule,
+    look_systems: Schedule,
+    overview_systems: Schedule,
+    character_systems: Schedule,
     config: Config,
+    flow: GameFlow,
+    menu: Menu,
+    can_continue: bool,
 }
 
 struct NewGameData {
@@ -84,45 +121,80 @@ struct NewGameData {
 }
 
 impl State {
-    fn new(config: Config, level: usize) -> Self {
-        let NewGameData { ecs, resources } = State::new_game_data(&config, level);
+    fn new(config: Config) -> Self {
         Self {
-            ecs,
-            resources,
+            ecs: World::default(),
+            resources: Resources::default(),
             input_systems: build_input_schedule(),
             player_systems: build_player_schedule(),
             monster_systems: build_monster_schedule(),
+            inventory_systems: build_inventory_schedule(),
+            trade_systems: build_trade_schedule(),
+            help_systems: build_help_schedule(),
+            look_systems: build_look_schedule(),
+            overview_systems: build_overview_schedule(),
+            character_systems: build_character_schedule(),
             config,
+            flow: GameFlow::MainMenu,
+            menu: Menu::default(),
+            can_continue: Checkpoint::exists(),
         }
     }
 
     fn new_game_data(config: &Config, level: usize) -> NewGameData {
         let mut ecs = World::default();
         let mut resources = Resources::default();
-        let mut rng = RandomNumberGenerator::new();
+        let mut rng = config.rng_for_level(level);
         let mut map_builder = MapBuilder::build(&config, &mut rng);
-        spawn_player(
-            &mut ecs,
-            map_builder.player_start.expect("What?? No player?"),
-        );
+        let player_start = map_builder.player_start.expect("What?? No player?");
+        let player = spawn_player(&mut ecs, player_start);
+        if config.hunger {
+            ecs.entry(player).unwrap().add_component(Hunger::default());
+        }
+        spawn_companion(&mut ecs, &map_builder.map, player_start);
 
-        let exit_idx = map_builder
-            .map
-            .point2d_to_index(map_builder.amulet_start.expect("There was no Amulet!!"));
-        map_builder.map.tiles[exit_idx] = TileType::Exit;
+        let amulet_start = map_builder.amulet_start.expect("There was no Amulet!!");
+        if level == AMULET_LEVEL {
+            spawn_amulet_of_yala(&mut ecs, amulet_start);
+        } else {
+            let exit_idx = map_builder.map.point2d_to_index(amulet_start);
+            map_builder.map.tiles[exit_idx] = TileType::Exit;
+        }
         spawn_level(&mut ecs, &mut rng, level, &map_builder.monster_spawns);
-        resources.insert(map_builder.map);
+        if let Some(shop_pos) = map_builder.shop_start {
+            spawn_merchant(&mut ecs, &mut rng, level, shop_pos);
+        }
+        map_builder
+            .keys
+            .iter()
+            .for_each(|(pos, id)| spawn_key(&mut ecs, *pos, *id));
+        spawn_torches(&mut ecs, &mut rng, &map_builder.map);
+        resources.insert(Environment::new(&map_builder.map, &map_builder.theme));
+        resources.insert(LightMap::new(&map_builder.map));
         let WorldDimensions {
             display_width,
             display_height,
             ..
         } = config.world_dimensions;
         resources.insert(Camera::new(
+            config.camera,
+            &map_builder.map,
             map_builder.player_start.unwrap(),
             display_width,
             display_height,
         ));
+        resources.insert(map_builder.map);
         resources.insert(TurnState::AwaitingInput);
+        resources.insert(InventoryScreen::default());
+        resources.insert(TradeScreen::default());
+        resources.insert(LookScreen::default());
+        resources.insert(OverviewScreen::default());
+        resources.insert(Statistics::default());
+        resources.insert(TravelPlan::default());
+        resources.insert(MessageLog::default());
+        resources.insert(config.clone());
+        resources.insert(KeyBindings::load());
+        resources.insert(Identification::new(&unidentified_items(), &mut rng));
         resources.insert(rng);
         resources.insert(map_builder.theme);
         NewGameData { ecs, resources }
@@ -150,10 +222,14 @@ impl State {
             "Don't worry, you can always try again with a new hero.",
         );
         ctx.print_color_centered(9, GREEN, BLACK, "Press 1 to play again.");
+        ctx.print_color_centered(
+            10,
+            GREEN,
+            BLACK,
+            "Press Escape to return to the title screen.",
+        );
 
-        if let Some(VirtualKeyCode::Key1) = ctx.key {
-            self.reset_game_state(0);
-        }
+        self.finished_game_input(ctx.key);
     }
 
     fn victory(&mut self, ctx: &mut BTerm) {
@@ -172,9 +248,21 @@ impl State {
             "Your town is saved, and you can return to your normal life.",
         );
         ctx.print_color_centered(7, GREEN, BLACK, "Press 1 to play again.");
+        ctx.print_color_centered(
+            8,
+            GREEN,
+            BLACK,
+            "Press Escape to return to the title screen.",
+        );
+
+        self.finished_game_input(ctx.key);
+    }
 
-        if let Some(VirtualKeyCode::Key1) = ctx.key {
-            self.reset_game_state(0);
+    fn finished_game_input(&mut self, key: Option<VirtualKeyCode>) {
+        match key {
+            Some(VirtualKeyCode::Key1) => self.reset_game_state(0),
+            Some(VirtualKeyCode::Escape) => self.return_to_title(),
+            _ => (),
         }
     }
 
@@ -182,18 +270,172 @@ impl State {
         let NewGameData { ecs, resources } = Self::new_game_data(&self.config, level);
         self.ecs = ecs;
         self.resources = resources;
+        self.flow = GameFlow::Playing;
+        self.checkpoint();
+    }
+
+    fn continue_game(&mut self) {
+        if let Some(checkpoint) = Checkpoint::load() {
+            self.config = checkpoint.config.clone();
+            let NewGameData { ecs, resources } =
+                Self::new_game_data(&self.config, checkpoint.map_level as usize);
+            self.ecs = ecs;
+            self.resources = resources;
+            checkpoint.restore(&mut self.ecs, &mut self.resources);
+            self.flow = GameFlow::Playing;
+        }
+    }
+
+    fn checkpoint(&self) {
+        if let Some(checkpoint) = Checkpoint::capture(&self.ecs, &self.resources, &self.config) {
+            checkpoint.write();
+        }
+    }
+
+    fn return_to_title(&mut self) {
+        self.flow = GameFlow::MainMenu;
+        self.menu = Menu::default();
+        self.can_continue = Checkpoint::exists();
+    }
+
+    fn main_menu(&mut self, ctx: &mut BTerm) {
+        ctx.set_active_console(HUD_LAYER.id);
+        ctx.print_color_centered(2, YELLOW, BLACK, "Dungeon Crawler");
+        MAIN_MENU.iter().enumerate().for_each(|(n, entry)| {
+            let color = match (n == self.menu.selected, *entry) {
+                (_, MainMenuEntry::Continue) if !self.can_continue => DARK_GRAY,
+                (true, _) => YELLOW,
+                (false, _) => WHITE,
+            };
+            ctx.print_color_centered(6 + n as i32 * 2, color, BLACK, entry.label());
+        });
+        ctx.print_color_centered(16, GREEN, BLACK, "Up / Down to choose, Enter to select.");
+
+        if let Some(key) = ctx.key {
+            self.menu.navigate(key, MAIN_MENU.len());
+            if key == VirtualKeyCode::Return {
+                match MAIN_MENU[self.menu.selected] {
+                    MainMenuEntry::NewGame => self.reset_game_state(0),
+                    MainMenuEntry::Continue => self.continue_game(),
+                    MainMenuEntry::Options => {
+                        self.flow = GameFlow::Options;
+                        self.menu = Menu::default();
+                    }
+                    MainMenuEntry::Quit => ctx.quit(),
+                }
+            }
+        }
+    }
+
+    fn options(&mut self, ctx: &mut BTerm) {
+        ctx.set_active_console(HUD_LAYER.id);
+        ctx.print_color_centered(2, YELLOW, BLACK, "Options");
+        OPTIONS_MENU.iter().enumerate().for_each(|(n, entry)| {
+            let color = if n == self.menu.selected {
+                YELLOW
+            } else {
+                WHITE
+            };
+            ctx.print_color_centered(6 + n as i32 * 2, color, BLACK, entry.label(&self.config));
+        });
+        ctx.print_color_centered(
+            20,
+            GREEN,
+            BLACK,
+            "Enter to change, type digits to set the seed, Escape to go back.",
+        );
+
+        if let Some(key) = ctx.key {
+            self.menu.navigate(key, OPTIONS_MENU.len());
+            let entry = OPTIONS_MENU[self.menu.selected];
+            match (key, entry) {
+                (VirtualKeyCode::Escape, _) | (VirtualKeyCode::Return, OptionsEntry::Back) => {
+                    self.return_to_title();
+                }
+                (VirtualKeyCode::Return, _) => {
+                    entry.change(&mut self.config, &mut RandomNumberGenerator::new());
+                }
+                (_, OptionsEntry::Seed) => self.config.seed = edit_seed(self.config.seed, key),
+                _ => (),
+            }
+        }
+    }
+
+    fn play(&mut self, ctx: &mut BTerm) {
+        self.resources.insert(ctx.key);
+        ctx.set_active_console(ENTITY_LAYER.id);
+        let mouse_pos = Point::from_tuple(ctx.mouse_pos());
+        let clicked = if ctx.left_click {
+            let camera = self.resources.get::<Camera>().unwrap();
+            Some(mouse_pos + Point::new(camera.left_x, camera.top_y))
+        } else {
+            None
+        };
+        self.resources.insert(mouse_pos);
+        self.resources.insert(MouseClick(clicked));
+        let current_state = self.resources.get::<TurnState>().unwrap().clone();
+        match current_state {
+            TurnState::AwaitingInput if ctx.key == Some(VirtualKeyCode::Escape) => {
+                self.return_to_title()
+            }
+            TurnState::AwaitingInput => self
+                .input_systems
+                .execute(&mut self.ecs, &mut self.resources),
+            TurnState::PlayerTurn => self
+                .player_systems
+                .execute(&mut self.ecs, &mut self.resources),
+            TurnState::MonsterTurn => self
+                .monster_systems
+                .execute(&mut self.ecs, &mut self.resources),
+            TurnState::GameOver => self.game_over(ctx),
+            TurnState::Victory => self.victory(ctx),
+            TurnState::NextLevel => self.advance_level(),
+            TurnState::Inventory => self
+                .inventory_systems
+                .execute(&mut self.ecs, &mut self.resources),
+            TurnState::Trade => self
+                .trade_systems
+                .execute(&mut self.ecs, &mut self.resources),
+            TurnState::Help => self
+                .help_systems
+                .execute(&mut self.ecs, &mut self.resources),
+            TurnState::Look => self
+                .look_systems
+                .execute(&mut self.ecs, &mut self.resources),
+            TurnState::MapOverview => self
+                .overview_systems
+                .execute(&mut self.ecs, &mut self.resources),
+            TurnState::CharacterSheet => self
+                .character_systems
+                .execute(&mut self.ecs, &mut self.resources),
+        }
+        let next_state = *self.resources.get::<TurnState>().unwrap();
+        if next_state != current_state
+            && matches!(next_state, TurnState::GameOver | TurnState::Victory)
+        {
+            Checkpoint::delete();
+        }
     }
 
     fn advance_level(&mut self) {
-        let player_entity = *<Entity>::query()
+        let (player_entity, player_pos) = <(Entity, &Point)>::query()
             .filter(component::<Player>())
-            .iter(&mut self.ecs)
-            .nth(0)
+            .iter(&self.ecs)
+            .find_map(|(entity, pos)| Some((*entity, *pos)))
             .unwrap();
 
         use std::collections::HashSet;
         let mut entities_to_keep = HashSet::new();
         entities_to_keep.insert(player_entity);
+        <(Entity, &Point)>::query()
+            .filter(component::<Companion>())
+            .iter(&self.ecs)
+            .filter(|(_entity, pos)| {
+                DistanceAlg::Pythagoras.distance2d(**pos, player_pos) <= COMPANION_FOLLOW_DISTANCE
+            })
+            .for_each(|(entity, _pos)| {
+                entities_to_keep.insert(*entity);
+            });
         <(Entity, &Carried)>::query()
             .iter(&self.ecs)
             .filter(|(_entity, carry)| carry.by == player_entity)
@@ -213,7 +455,12 @@ impl State {
             .iter_mut(&mut self.ecs)
             .for_each(|fov| fov.visible_tiles = None);
 
-        let mut rng = RandomNumberGenerator::new();
+        let next_level = <&Player>::query()
+            .iter(&self.ecs)
+            .map(|player| player.map_level as usize + 1)
+            .nth(0)
+            .unwrap_or(1);
+        let mut rng = self.config.rng_for_level(next_level);
         let mut map_builder = MapBuilder::build(&self.config, &mut rng);
 
         let mut map_level = 0;
@@ -226,8 +473,14 @@ impl State {
                 pos.x = ps.x;
                 pos.y = ps.y;
             });
+        <&mut Point>::query()
+            .filter(component::<Companion>())
+            .iter_mut(&mut self.ecs)
+            .for_each(|pos| {
+                *pos = companion_start(&map_builder.map, map_builder.player_start.unwrap());
+            });
 
-        if map_level == 2 {
+        if map_level as usize == AMULET_LEVEL {
             spawn_amulet_of_yala(&mut self.ecs, map_builder.amulet_start.unwrap());
         } else {
             let exit_idx = map_builder
@@ -242,14 +495,29 @@ impl State {
             map_level as usize,
             &map_builder.monster_spawns,
         );
-        self.resources.insert(map_builder.map);
+        if let Some(shop_pos) = map_builder.shop_start {
+            spawn_merchant(&mut self.ecs, &mut rng, map_level as usize, shop_pos);
+        }
+        map_builder
+            .keys
+            .iter()
+            .for_each(|(pos, id)| spawn_key(&mut self.ecs, *pos, *id));
+        spawn_torches(&mut self.ecs, &mut rng, &map_builder.map);
+        self.resources
+            .insert(Environment::new(&map_builder.map, &map_builder.theme));
+        self.resources.insert(LightMap::new(&map_builder.map));
         self.resources.insert(Camera::new(
+            self.config.camera,
+            &map_builder.map,
             map_builder.player_start.unwrap(),
             self.config.world_dimensions.display_width,
             self.config.world_dimensions.display_height,
         ));
+        self.resources.insert(map_builder.map);
         self.resources.insert(TurnState::AwaitingInput);
+        self.resources.insert(TravelPlan::default());
         self.resources.insert(map_builder.theme);
+        self.checkpoint();
     }
 }
 
@@ -259,23 +527,10 @@ impl GameState for State {
             ctx.set_active_console((*layer).id);
             ctx.cls();
         }
-        self.resources.insert(ctx.key);
-        ctx.set_active_console(ENTITY_LAYER.id);
-        self.resources.insert(Point::from_tuple(ctx.mouse_pos()));
-        let current_state = self.resources.get::<TurnState>().unwrap().clone();
-        match current_state {
-            TurnState::AwaitingInput => self
-                .input_systems
-                .execute(&mut self.ecs, &mut self.resources),
-            TurnState::PlayerTurn => self
-                .player_systems
-                .execute(&mut self.ecs, &mut self.resources),
-            TurnState::MonsterTurn => self
-                .monster_systems
-                .execute(&mut self.ecs, &mut self.resources),
-            TurnState::GameOver => self.game_over(ctx),
-            TurnState::Victory => self.victory(ctx),
-            TurnState::NextLevel => self.advance_level(),
+        match self.flow {
+            GameFlow::MainMenu => self.main_menu(ctx),
+            GameFlow::Options => self.options(ctx),
+            GameFlow::Playing => self.play(ctx),
         }
         render_draw_buffer(ctx).expect("Render Error");
     }
@@ -305,5 +560,5 @@ fn main() -> BError {
         )
         .build()?;
 
-    main_loop(context, State::new(config, 0))
+    main_loop(context, State::new(config))
 }
@@ -1,15 +1,35 @@
 use crate::prelude::*;
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicU64, Ordering};
 
-#[derive(Copy, Clone, PartialEq)]
+static NEXT_REVISION: AtomicU64 = AtomicU64::new(0);
+const TRAP_PATH_COST: f32 = 20.0;
+
+fn next_revision() -> u64 {
+    NEXT_REVISION.fetch_add(1, Ordering::Relaxed)
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub enum TileType {
     Wall,
     Floor,
     Exit,
+    Door,
+    OpenDoor,
+    LockedDoor(u8),
+    SecretDoor,
+    DeepWater,
+    Lava,
+    Chasm,
+    Bridge,
 }
 
 pub struct Map {
     pub tiles: Vec<TileType>,
     pub revealed_tiles: Vec<bool>,
+    pub hazards: HashSet<usize>,
+    pub revision: u64,
+    pub allow_diagonals: bool,
     pub width: i32,
     pub height: i32,
 }
@@ -20,6 +40,9 @@ impl Map {
         Self {
             tiles: vec![TileType::Floor; num_tiles],
             revealed_tiles: vec![false; num_tiles],
+            hazards: HashSet::new(),
+            revision: next_revision(),
+            allow_diagonals: false,
             width,
             height,
         }
@@ -53,8 +76,91 @@ impl Map {
 
     pub fn can_enter_tile(&self, point: Point) -> bool {
         self.in_bounds(point)
-            && (self.tiles[self.index_for(point.x, point.y)] == TileType::Floor
-                || self.tiles[self.index_for(point.x, point.y)] == TileType::Exit)
+            && matches!(
+                self.tiles[self.index_for(point.x, point.y)],
+                TileType::Floor
+                    | TileType::Exit
+                    | TileType::OpenDoor
+                    | TileType::DeepWater
+                    | TileType::Lava
+                    | TileType::Bridge
+            )
+    }
+
+    pub fn is_safe_floor(&self, point: Point) -> bool {
+        self.in_bounds(point)
+            && matches!(
+                self.tiles[self.index_for(point.x, point.y)],
+                TileType::Floor | TileType::Bridge
+            )
+    }
+
+    pub fn swallows_items(&self, point: Point) -> bool {
+        self.in_bounds(point)
+            && matches!(
+                self.tiles[self.index_for(point.x, point.y)],
+                TileType::DeepWater | TileType::Lava | TileType::Chasm
+            )
+    }
+
+    pub fn movement_cost(&self, idx: usize) -> f32 {
+        match self.tiles[idx] {
+            TileType::DeepWater | TileType::Door => 2.0,
+            TileType::Lava => 10.0,
+            _ => 1.0,
+        }
+    }
+
+    pub fn can_path_through(&self, point: Point) -> bool {
+        self.can_enter_tile(point)
+            || (self.in_bounds(point)
+                && self.tiles[self.index_for(point.x, point.y)] == TileType::Door)
+    }
+
+    pub fn can_step(&self, from: Point, delta: Point) -> bool {
+        if delta.x == 0 || delta.y == 0 {
+            return true;
+        }
+        self.allow_diagonals
+            && self.can_path_through(from + Point::new(delta.x, 0))
+            && self.can_path_through(from + Point::new(0, delta.y))
+    }
+
+    pub fn steps(&self) -> &'static [Point] {
+        if self.allow_diagonals {
+            &*EIGHT_WAYS
+        } else {
+            &*FOUR_WAYS
+        }
+    }
+
+    pub fn set_tile(&mut self, idx: usize, tile: TileType) {
+        if self.tiles[idx] != tile {
+            self.tiles[idx] = tile;
+            self.revision = next_revision();
+        }
+    }
+
+    pub fn dig(&mut self, point: Point) -> bool {
+        if self.in_floor_bounds(point) && self.tiles[self.point2d_to_index(point)] == TileType::Wall
+        {
+            self.set_tile(self.point2d_to_index(point), TileType::Floor);
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn add_hazard(&mut self, idx: usize) {
+        if self.hazards.insert(idx) {
+            self.revision = next_revision();
+        }
+    }
+
+    pub fn remove_hazard(&mut self, idx: usize) {
+        if self.hazards.remove(&idx) {
+            self.revision = next_revision();
+        }
     }
 
     pub fn try_idx(&self, point: Point) -> Option<usize> {
@@ -67,9 +173,10 @@ impl Map {
 
     fn valid_exit(&self, loc: Point, delta: Point) -> Option<usize> {
         let destination = loc + delta;
-        if self.in_bounds(destination) {
-            if self.can_enter_tile(destination) {
-                Some(self.point2d_to_index(destination))
+        if self.in_bounds(destination) && self.can_step(loc, delta) {
+            let idx = self.point2d_to_index(destination);
+            if self.can_path_through(destination) {
+                Some(idx)
             } else {
                 None
             }
@@ -97,33 +204,51 @@ impl Map {
     }
 }
 lazy_static! {
-    static ref LEFT: Point = Point::new(-1, 0);
-    static ref RIGHT: Point = Point::new(1, 0);
-    static ref UP: Point = Point::new(0, -1);
-    static ref DOWN: Point = Point::new(0, 1);
+    static ref FOUR_WAYS: Vec<Point> = vec![
+        Point::new(-1, 0),
+        Point::new(1, 0),
+        Point::new(0, -1),
+        Point::new(0, 1),
+    ];
+    static ref EIGHT_WAYS: Vec<Point> = vec![
+        Point::new(-1, 0),
+        Point::new(1, 0),
+        Point::new(0, -1),
+        Point::new(0, 1),
+        Point::new(-1, -1),
+        Point::new(1, -1),
+        Point::new(-1, 1),
+        Point::new(1, 1),
+    ];
 }
 
 impl BaseMap for Map {
     fn is_opaque(&self, idx: usize) -> bool {
-        self.tiles[idx as usize] != TileType::Floor
+        !matches!(
+            self.tiles[idx as usize],
+            TileType::Floor
+                | TileType::OpenDoor
+                | TileType::DeepWater
+                | TileType::Lava
+                | TileType::Chasm
+                | TileType::Bridge
+        )
     }
 
     fn get_available_exits(&self, idx: usize) -> SmallVec<[(usize, f32); 10]> {
         let mut exits = SmallVec::new();
         let location = self.index_to_point2d(idx);
 
-        if let Some(idx) = self.valid_exit(location, *LEFT) {
-            exits.push((idx, 1.0))
-        }
-        if let Some(idx) = self.valid_exit(location, *RIGHT) {
-            exits.push((idx, 1.0))
-        }
-        if let Some(idx) = self.valid_exit(location, *UP) {
-            exits.push((idx, 1.0))
-        }
-        if let Some(idx) = self.valid_exit(location, *DOWN) {
-            exits.push((idx, 1.0))
-        }
+        self.steps().iter().for_each(|delta| {
+            if let Some(idx) = self.valid_exit(location, *delta) {
+                let cost = if delta.x != 0 && delta.y != 0 {
+                    self.movement_cost(idx) * std::f32::consts::SQRT_2
+                } else {
+                    self.movement_cost(idx)
+                };
+                exits.push((idx, cost))
+            }
+        });
         exits
     }
 
@@ -147,3 +272,67 @@ impl Algorithm2D for Map {
             point.y < self.height
     }
 }
+
+pub struct TrapAwareMap<'a> {
+    map: &'a Map,
+    traps: &'a HashSet<usize>,
+}
+
+impl<'a> TrapAwareMap<'a> {
+    pub fn new(map: &'a Map, traps: &'a HashSet<usize>) -> Self {
+        Self { map, traps }
+    }
+}
+
+impl BaseMap for TrapAwareMap<'_> {
+    fn get_available_exits(&self, idx: usize) -> SmallVec<[(usize, f32); 10]> {
+        self.map
+            .get_available_exits(idx)
+            .into_iter()
+            .map(|(exit, cost)| {
+                if self.traps.contains(&exit) {
+                    (exit, cost + TRAP_PATH_COST)
+                } else {
+                    (exit, cost)
+                }
+            })
+            .collect()
+    }
+
+    fn get_pathing_distance(&self, idx1: usize, idx2: usize) -> f32 {
+        self.map.get_pathing_distance(idx1, idx2)
+    }
+}
+
+#[test]
+fn diagonal_exits_follow_the_map_setting() {
+    let mut map = Map::new(5, 5);
+    let center = map.point2d_to_index(Point::new(2, 2));
+    assert_eq!(map.get_available_exits(center).len(), 4);
+    map.allow_diagonals = true;
+    assert_eq!(map.get_available_exits(center).len(), 8);
+    let idx = map.point2d_to_index(Point::new(1, 2));
+    map.tiles[idx] = TileType::Wall;
+    assert_eq!(map.get_available_exits(center).len(), 5);
+}
+
+#[test]
+fn known_traps_are_avoided_but_still_passable() {
+    let mut map = Map::new(3, 2);
+    let trap = map.point2d_to_index(Point::new(1, 0));
+    let traps: HashSet<usize> = [trap].iter().cloned().collect();
+    let path = a_star_search(
+        map.point2d_to_index(Point::new(0, 0)),
+        map.point2d_to_index(Point::new(2, 0)),
+        &TrapAwareMap::new(&map, &traps),
+    );
+    assert!(path.success && !path.steps.contains(&trap));
+    let idx = map.point2d_to_index(Point::new(1, 1));
+    map.tiles[idx] = TileType::Wall;
+    let path = a_star_search(
+        map.point2d_to_index(Point::new(0, 0)),
+        map.point2d_to_index(Point::new(2, 0)),
+        &TrapAwareMap::new(&map, &traps),
+    );
+    assert!(path.success && path.steps.contains(&trap));
+}
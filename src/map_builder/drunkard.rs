@@ -59,6 +59,10 @@ impl MapArchitect for DrunkardsWalkArchitect {
             monster_spawns: vec![],
             player_start: None,
             amulet_start: None,
+            shop_start: None,
+            fortress: None,
+            doors: Vec::new(),
+            keys: Vec::new(),
             theme: None,
             width: self.width,
             height: self.height,
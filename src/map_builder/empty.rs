@@ -21,6 +21,10 @@ impl MapArchitect for EmptyArchitect {
             monster_spawns: Vec::new(),
             player_start: None,
             amulet_start: None,
+            shop_start: None,
+            fortress: None,
+            doors: Vec::new(),
+            keys: Vec::new(),
             theme: None,
             width: self.width,
             height: self.height,
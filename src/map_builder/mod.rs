@@ -9,7 +9,7 @@ mod themes;
 use crate::map_builder::themes::{DungeonTheme, ForestTheme};
 use automata::CellularAutomataArchitect;
 use drunkard::DrunkardsWalkArchitect;
-use prefab::apply_prefab;
+use prefab::{apply_prefab, apply_shop};
 use rooms::RoomsArchitect;
 use std::{
     cmp::{max, min, Ordering},
@@ -20,7 +20,12 @@ pub use themes::*;
 const TILES_TO_ROOM_RATIO: usize = 200;
 const MIN_ROOMS: usize = 10;
 
-const UNREACHABLE: f32 = std::f32::MAX;
+const DOOR_CHANCE_PERCENT: i32 = 60;
+const LOCKED_DOOR_CHANCE_PERCENT: i32 = 50;
+const MIN_KEY_DISTANCE: f32 = 5.0;
+const SECRET_DOOR_CHANCE_PERCENT: i32 = 25;
+const TILES_TO_TERRAIN_POOL_RATIO: usize = 800;
+const MAX_POOL_RADIUS: i32 = 3;
 
 pub trait MapArchitect {
     fn build(&mut self, rng: &mut RandomNumberGenerator) -> MapBuilder;
@@ -34,6 +39,10 @@ pub struct MapBuilder {
     pub monster_spawns: Vec<Point>,
     pub player_start: Option<Point>,
     pub amulet_start: Option<Point>,
+    pub shop_start: Option<Point>,
+    pub fortress: Option<Rect>,
+    pub doors: Vec<Point>,
+    pub keys: Vec<(Point, u8)>,
     pub theme: Option<Box<dyn MapTheme>>,
 }
 
@@ -68,7 +77,12 @@ impl MapBuilder {
             }
         }
         .build(rng);
+        mb.map.allow_diagonals = config.diagonals;
         apply_prefab(&mut mb, rng);
+        apply_shop(&mut mb, rng);
+        mb.lock_door(rng);
+        mb.hide_doors(rng);
+        mb.add_terrain(rng);
         mb.theme = Some(match config.theme {
             ThemeChoice::Dungeon => DUNGEON_THEME_CREATOR(),
             ThemeChoice::Forest => FOREST_THEME_CREATOR(),
@@ -267,6 +281,208 @@ impl MapBuilder {
         }
     }
 
+    fn place_doors(&mut self, rng: &mut RandomNumberGenerator) {
+        let mut doors = Vec::new();
+        for room in self.rooms.iter() {
+            let ring = Rect::with_exact(room.x1 - 1, room.y1 - 1, room.x2 + 1, room.y2 + 1);
+            for pt in ring.point_set() {
+                let on_edge = pt.x == ring.x1
+                    || pt.x == ring.x2 - 1
+                    || pt.y == ring.y1
+                    || pt.y == ring.y2 - 1;
+                if !on_edge || !is_floor(&self.map, pt) {
+                    continue;
+                }
+                if is_doorway(&self.map, pt) && rng.range(0, 100) < DOOR_CHANCE_PERCENT {
+                    doors.push(pt);
+                }
+            }
+        }
+        doors.iter().for_each(|pt| {
+            let idx = self.map.point2d_to_index(*pt);
+            self.map.tiles[idx] = TileType::Door;
+        });
+        self.doors.extend(doors);
+    }
+
+    fn lock_door(&mut self, rng: &mut RandomNumberGenerator) {
+        const KEY_ID: u8 = 0;
+        let player_start = match self.player_start {
+            Some(player_start) => player_start,
+            None => return,
+        };
+        let map = &self.map;
+        self.doors
+            .retain(|pt| map.tiles[map.point2d_to_index(*pt)] == TileType::Door);
+        if self.doors.is_empty() || rng.range(0, 100) >= LOCKED_DOOR_CHANCE_PERCENT {
+            return;
+        }
+        let door = self.doors[rng.range(0, self.doors.len())];
+        let door_idx = self.map.point2d_to_index(door);
+        self.map.tiles[door_idx] = TileType::LockedDoor(KEY_ID);
+
+        let dijkstra_map = DijkstraMap::new(
+            self.width,
+            self.height,
+            &vec![self.map.point2d_to_index(player_start)],
+            &self.map,
+            DISTANCE_MAX_DEPTH,
+        );
+        let key_spots: Vec<Point> = dijkstra_map
+            .map
+            .iter()
+            .enumerate()
+            .filter(|(idx, distance)| {
+                **distance != UNREACHABLE
+                    && self.map.tiles[*idx] == TileType::Floor
+                    && DistanceAlg::Pythagoras
+                        .distance2d(player_start, self.map.index_to_point2d(*idx))
+                        >= MIN_KEY_DISTANCE
+            })
+            .map(|(idx, _)| self.map.index_to_point2d(idx))
+            .filter(|pt| !self.monster_spawns.contains(pt) && Some(*pt) != self.amulet_start)
+            .collect();
+        if let Some(key_pos) = rng.random_slice_entry(&key_spots) {
+            #[cfg(debug_assertions)]
+            println!("Locked door at {:?}, key at {:?}", door, key_pos);
+            self.keys.push((*key_pos, KEY_ID));
+        } else {
+            self.map.tiles[door_idx] = TileType::Door;
+        }
+    }
+
+    fn hide_doors(&mut self, rng: &mut RandomNumberGenerator) {
+        let protected = self.protected_points();
+        let map = &self.map;
+        let passages = (0..map.tiles.len())
+            .map(|idx| map.index_to_point2d(idx))
+            .filter(|pt| {
+                map.in_floor_bounds(*pt)
+                    && is_floor(map, *pt)
+                    && is_passage_mouth(map, *pt)
+                    && !protected.contains(pt)
+            });
+        let candidates: Vec<Point> = self
+            .doors
+            .iter()
+            .filter(|pt| map.tiles[map.point2d_to_index(**pt)] == TileType::Door)
+            .copied()
+            .chain(passages)
+            .collect();
+        for door in candidates {
+            if rng.range(0, 100) >= SECRET_DOOR_CHANCE_PERCENT {
+                continue;
+            }
+            let idx = self.map.point2d_to_index(door);
+            let original = self.map.tiles[idx];
+            self.map.tiles[idx] = TileType::SecretDoor;
+            if self.is_completable() {
+                #[cfg(debug_assertions)]
+                println!("Secret door at {:?}", door);
+            } else {
+                self.map.tiles[idx] = original;
+            }
+        }
+    }
+
+    fn add_terrain(&mut self, rng: &mut RandomNumberGenerator) {
+        const TERRAIN_TYPES: [TileType; 3] = [TileType::DeepWater, TileType::Lava, TileType::Chasm];
+        let protected = self.protected_points();
+
+        let num_pools = (self.width * self.height) as usize / TILES_TO_TERRAIN_POOL_RATIO;
+        for _ in 0..num_pools {
+            let terrain = *rng.random_slice_entry(&TERRAIN_TYPES).unwrap();
+            let center = Point::new(rng.range(1, self.width - 1), rng.range(1, self.height - 1));
+            let radius = rng.range(1, MAX_POOL_RADIUS + 1);
+            let pool: Vec<usize> = (-radius..=radius)
+                .cartesian_product(-radius..=radius)
+                .map(|(dy, dx)| center + Point::new(dx, dy))
+                .filter(|pt| {
+                    self.map.in_floor_bounds(*pt)
+                        && self.map.tiles[self.map.point2d_to_index(*pt)] == TileType::Floor
+                        && DistanceAlg::Pythagoras.distance2d(center, *pt) <= radius as f32
+                        && protected
+                            .iter()
+                            .all(|p| DistanceAlg::Pythagoras.distance2d(*p, *pt) > 1.5)
+                })
+                .map(|pt| self.map.point2d_to_index(pt))
+                .collect();
+
+            pool.iter().for_each(|idx| {
+                let pt = self.map.index_to_point2d(*idx);
+                self.map.tiles[*idx] = if terrain == TileType::Chasm && pt.y == center.y {
+                    TileType::Bridge
+                } else {
+                    terrain
+                };
+            });
+            if !self.is_completable() {
+                pool.iter()
+                    .for_each(|idx| self.map.tiles[*idx] = TileType::Floor);
+            }
+        }
+    }
+
+    fn protected_points(&self) -> Vec<Point> {
+        let mut protected: Vec<Point> = self.monster_spawns.clone();
+        protected.extend(self.player_start);
+        protected.extend(self.amulet_start);
+        protected.extend(self.shop_start);
+        protected.extend(self.keys.iter().map(|(pt, _)| *pt));
+        protected
+    }
+
+    fn is_completable(&mut self) -> bool {
+        let player_start = match self.player_start {
+            Some(player_start) => player_start,
+            None => return false,
+        };
+        let reachable_from_start = |map: &Map, targets: &[Point]| {
+            let dijkstra_map = DijkstraMap::new(
+                map.width,
+                map.height,
+                &vec![map.point2d_to_index(player_start)],
+                map,
+                DISTANCE_MAX_DEPTH,
+            );
+            targets
+                .iter()
+                .all(|pt| dijkstra_map.map[map.point2d_to_index(*pt)] != UNREACHABLE)
+        };
+
+        let lava: Vec<usize> = (0..self.map.tiles.len())
+            .filter(|idx| self.map.tiles[*idx] == TileType::Lava)
+            .collect();
+        lava.iter()
+            .for_each(|idx| self.map.tiles[*idx] = TileType::Wall);
+        let keys: Vec<Point> = self.keys.iter().map(|(pt, _)| *pt).collect();
+        let keys_reachable = reachable_from_start(&self.map, &keys);
+
+        let locked: Vec<(usize, TileType)> = self
+            .map
+            .tiles
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| matches!(t, TileType::LockedDoor(_)))
+            .map(|(idx, t)| (idx, *t))
+            .collect();
+        locked
+            .iter()
+            .for_each(|(idx, _)| self.map.tiles[*idx] = TileType::Door);
+        let goals: Vec<Point> = self
+            .amulet_start
+            .into_iter()
+            .chain(self.shop_start)
+            .collect();
+        let completable = keys_reachable && reachable_from_start(&self.map, &goals);
+        locked
+            .iter()
+            .for_each(|(idx, tile)| self.map.tiles[*idx] = *tile);
+        lava.iter()
+            .for_each(|idx| self.map.tiles[*idx] = TileType::Lava);
+        completable
+    }
+
     fn spawn_monsters(&self, start: &Point, rng: &mut RandomNumberGenerator) -> Vec<Point> {
         let num_monsters = (self.width * self.height / 40) as usize;
         const MIN_MONSTER_DISTANCE: f32 = 10.0;
@@ -293,6 +509,35 @@ impl MapBuilder {
     }
 }
 
+fn is_floor(map: &Map, pt: Point) -> bool {
+    map.try_idx(pt)
+        .map_or(false, |idx| map.tiles[idx] == TileType::Floor)
+}
+
+fn is_wall(map: &Map, pt: Point) -> bool {
+    map.try_idx(pt)
+        .map_or(true, |idx| map.tiles[idx] == TileType::Wall)
+}
+
+fn is_doorway(map: &Map, pt: Point) -> bool {
+    let (left, right) = (pt + Point::new(-1, 0), pt + Point::new(1, 0));
+    let (up, down) = (pt + Point::new(0, -1), pt + Point::new(0, 1));
+    (is_wall(map, left) && is_wall(map, right) && is_floor(map, up) && is_floor(map, down))
+        || (is_wall(map, up) && is_wall(map, down) && is_floor(map, left) && is_floor(map, right))
+}
+
+fn is_passage_mouth(map: &Map, pt: Point) -> bool {
+    is_doorway(map, pt)
+        && [
+            Point::new(-1, 0),
+            Point::new(1, 0),
+            Point::new(0, -1),
+            Point::new(0, 1),
+        ]
+        .iter()
+        .any(|delta| is_floor(map, pt + *delta) && !is_doorway(map, pt + *delta))
+}
+
 #[derive(Debug, Eq)]
 struct Tunnel {
     pub start: Point,
@@ -364,6 +609,12 @@ pub fn display(
     const START: char = 'S';
     const END: char = 'E';
     const STAIRS: char = '>';
+    const DOOR: char = '+';
+    const LOCKED_DOOR: char = '=';
+    const SECRET_DOOR: char = '*';
+    const WATER: char = '~';
+    const LAVA: char = '^';
+    const CHASM: char = ' ';
 
     use colored::*;
     let mut output = vec!['.'; (map.width * map.height) as usize];
@@ -372,6 +623,13 @@ pub fn display(
         TileType::Floor => output[idx] = FLOOR,
         TileType::Wall => output[idx] = WALL,
         TileType::Exit => output[idx] = STAIRS,
+        TileType::Door | TileType::OpenDoor => output[idx] = DOOR,
+        TileType::LockedDoor(_) => output[idx] = LOCKED_DOOR,
+        TileType::SecretDoor => output[idx] = SECRET_DOOR,
+        TileType::DeepWater => output[idx] = WATER,
+        TileType::Lava => output[idx] = LAVA,
+        TileType::Chasm => output[idx] = CHASM,
+        TileType::Bridge => output[idx] = FLOOR,
     });
 
     if let Some(pos) = player_start {
@@ -403,6 +661,12 @@ pub fn display(
                 AMULET => print!("{}", AMULET.to_string().bright_magenta()),
                 START => print!("{}", START.to_string().bright_yellow()),
                 END => print!("{}", END.to_string().bright_yellow()),
+                DOOR => print!("{}", DOOR.to_string().yellow()),
+                LOCKED_DOOR => print!("{}", LOCKED_DOOR.to_string().bright_red()),
+                SECRET_DOOR => print!("{}", SECRET_DOOR.to_string().bright_cyan()),
+                WATER => print!("{}", WATER.to_string().bright_blue()),
+                LAVA => print!("{}", LAVA.to_string().red()),
+                CHASM => print!("{}", CHASM),
                 _ => print!("{}", ".".truecolor(64, 64, 64)),
             }
         }
@@ -412,4 +676,5 @@ pub fn display(
 
 pub trait MapTheme: Sync + Send {
     fn tile_to_render(&self, tile_type: TileType) -> FontCharType;
+    fn is_flammable(&self, tile_type: TileType) -> bool;
 }
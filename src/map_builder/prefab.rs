@@ -10,6 +10,10 @@ const OUTSIDE_FLOOR: char = '-';
 const WALL: char = '#';
 const MONSTER: char = 'M';
 const POSSIBLER_AMULET_POS: char = '.';
+const SHOP_FLOOR: char = '.';
+const MERCHANT: char = 'm';
+const MAX_SHOP_ATTEMPTS: usize = 50;
+const MIN_SHOP_AMULET_DISTANCE: f32 = 12.0;
 
 const FORTRESS: FortressStruct = FortressStruct {
     map_str: "
@@ -29,6 +33,20 @@ const FORTRESS: FortressStruct = FortressStruct {
     y: 11,
 };
 
+const SHOP: FortressStruct = FortressStruct {
+    map_str: "
+---------
+-#######-
+-#.....#-
+-#..m..#-
+-#.....#-
+-###.###-
+---------
+",
+    x: 9,
+    y: 7,
+};
+
 pub fn apply_prefab(mb: &mut MapBuilder, rng: &mut RandomNumberGenerator) {
     let amulet_pos = mb
         .amulet_start
@@ -67,6 +85,7 @@ pub fn apply_prefab(mb: &mut MapBuilder, rng: &mut RandomNumberGenerator) {
     let dimensions = Rect::with_size(placement.x, placement.y, FORTRESS.x, FORTRESS.y);
     let points = dimensions.point_set();
     mb.monster_spawns.retain(|pt| !points.contains(pt));
+    mb.fortress = Some(dimensions);
 
     #[cfg(debug_assertions)]
     println!("Prefab placed at {:?}", &placement);
@@ -88,3 +107,74 @@ pub fn apply_prefab(mb: &mut MapBuilder, rng: &mut RandomNumberGenerator) {
         }
     }
 }
+
+pub fn apply_shop(mb: &mut MapBuilder, rng: &mut RandomNumberGenerator) {
+    let player_pos = mb
+        .player_start
+        .expect("Can't place a shop without a player");
+    let amulet_pos = mb
+        .amulet_start
+        .expect("Can't place a shop without an amulet");
+
+    let shop_vec: Vec<char> = SHOP
+        .map_str
+        .chars()
+        .filter(|a| *a != '\r' && *a != '\n')
+        .collect();
+
+    for _ in 0..MAX_SHOP_ATTEMPTS {
+        let placement = Point::new(
+            rng.range(1, mb.map.width - SHOP.x),
+            rng.range(1, mb.map.height - SHOP.y),
+        );
+        let dimensions = Rect::with_size(placement.x, placement.y, SHOP.x, SHOP.y);
+        let points = dimensions.point_set();
+        let too_close = points.iter().any(|pt| {
+            *pt == player_pos
+                || DistanceAlg::Pythagoras.distance2d(*pt, amulet_pos) < MIN_SHOP_AMULET_DISTANCE
+        });
+        let overlaps_fortress = mb
+            .fortress
+            .map_or(false, |fortress| fortress.intersect(&dimensions));
+        if too_close || overlaps_fortress {
+            continue;
+        }
+
+        let original_tiles = mb.map.tiles.clone();
+        let mut merchant_pos = placement;
+        for (i, (ty, tx)) in (placement.y..placement.y + SHOP.y)
+            .cartesian_product(placement.x..placement.x + SHOP.x)
+            .enumerate()
+        {
+            let idx = mb.map.index_for(tx, ty);
+            match shop_vec[i] {
+                SHOP_FLOOR | OUTSIDE_FLOOR => mb.map.tiles[idx] = TileType::Floor,
+                MERCHANT => {
+                    mb.map.tiles[idx] = TileType::Floor;
+                    merchant_pos = Point::new(tx, ty);
+                }
+                WALL => mb.map.tiles[idx] = TileType::Wall,
+                c => println!("No idea what to do with [{}]", c),
+            }
+        }
+
+        let dijkstra_map = DijkstraMap::new(
+            mb.map.width,
+            mb.map.height,
+            &[mb.map.point2d_to_index(player_pos)],
+            &mb.map,
+            DISTANCE_MAX_DEPTH,
+        );
+        let reachable =
+            |pt: Point| dijkstra_map.map[mb.map.point2d_to_index(pt)] < DISTANCE_MAX_DEPTH;
+        if reachable(merchant_pos) && reachable(amulet_pos) {
+            mb.monster_spawns.retain(|pt| !points.contains(pt));
+            mb.shop_start = Some(merchant_pos);
+
+            #[cfg(debug_assertions)]
+            println!("Shop placed at {:?}", &placement);
+            return;
+        }
+        mb.map.tiles = original_tiles;
+    }
+}
@@ -21,6 +21,10 @@ impl MapArchitect for RoomsArchitect {
             monster_spawns: Vec::new(),
             player_start: None,
             amulet_start: None,
+            shop_start: None,
+            fortress: None,
+            doors: Vec::new(),
+            keys: Vec::new(),
             theme: None,
             width: self.width,
             height: self.height,
@@ -29,6 +33,7 @@ impl MapArchitect for RoomsArchitect {
         mb.fill(TileType::Wall);
         mb.build_random_rooms(rng);
         mb.build_corridors(rng);
+        mb.place_doors(rng);
         mb.player_start = Some(mb.rooms[0].center());
         mb.amulet_start = Some(mb.find_most_distant());
         for room in mb.rooms.iter().skip(1) {
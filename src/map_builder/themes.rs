@@ -12,10 +12,24 @@ impl MapTheme for DungeonTheme {
     fn tile_to_render(&self, tile_type: TileType) -> FontCharType {
         match tile_type {
             TileType::Floor => *DUNGEON_FLOOR_GLYPH,
-            TileType::Wall => *DUNGEON_WALL_GLYPH,
+            TileType::Wall | TileType::SecretDoor => *DUNGEON_WALL_GLYPH,
             TileType::Exit => *STAIRS_GLYPH,
+            TileType::Door => *DOOR_GLYPH,
+            TileType::OpenDoor => *OPEN_DOOR_GLYPH,
+            TileType::LockedDoor(_) => *LOCKED_DOOR_GLYPH,
+            TileType::DeepWater => *DUNGEON_WATER_GLYPH,
+            TileType::Lava => *LAVA_GLYPH,
+            TileType::Chasm => *CHASM_GLYPH,
+            TileType::Bridge => *BRIDGE_GLYPH,
         }
     }
+
+    fn is_flammable(&self, tile_type: TileType) -> bool {
+        matches!(
+            tile_type,
+            TileType::Door | TileType::OpenDoor | TileType::Bridge
+        )
+    }
 }
 
 pub struct ForestTheme {}
@@ -30,10 +44,24 @@ impl MapTheme for ForestTheme {
     fn tile_to_render(&self, tile_type: TileType) -> u16 {
         match tile_type {
             TileType::Floor => *FOREST_FLOOR_GLYPH,
-            TileType::Wall => *FOREST_WALL_GLYPH,
+            TileType::Wall | TileType::SecretDoor => *FOREST_WALL_GLYPH,
             TileType::Exit => *STAIRS_GLYPH,
+            TileType::Door => *DOOR_GLYPH,
+            TileType::OpenDoor => *OPEN_DOOR_GLYPH,
+            TileType::LockedDoor(_) => *LOCKED_DOOR_GLYPH,
+            TileType::DeepWater => *FOREST_WATER_GLYPH,
+            TileType::Lava => *LAVA_GLYPH,
+            TileType::Chasm => *CHASM_GLYPH,
+            TileType::Bridge => *BRIDGE_GLYPH,
         }
     }
+
+    fn is_flammable(&self, tile_type: TileType) -> bool {
+        matches!(
+            tile_type,
+            TileType::Floor | TileType::Door | TileType::OpenDoor | TileType::Bridge
+        )
+    }
 }
 
 const DUNGEON_FLOOR_CHAR: char = '.';
@@ -41,9 +69,22 @@ const DUNGEON_WALL_CHAR: char = '#';
 const FOREST_FLOOR_CHAR: char = ';';
 const FOREST_TREE_CHAR: char = '"';
 const STAIRS_CHAR: char = '>';
+const DOOR_CHAR: char = '+';
+const OPEN_DOOR_CHAR: char = '\'';
+const LOCKED_DOOR_CHAR: char = '=';
+const KEY_CHAR: char = 'k';
+const TORCH_CHAR: char = '¡';
+const DUNGEON_WATER_CHAR: char = '~';
+const FOREST_WATER_CHAR: char = '≈';
+const LAVA_CHAR: char = '▒';
+const CHASM_CHAR: char = '░';
+const BRIDGE_CHAR: char = '≡';
 const GOBLIN_CHAR: char = 'g';
 const ORC_CHAR: char = 'O';
 const PLAYER_CHAR: char = '@';
+const DOG_CHAR: char = 'd';
+const MERCHANT_CHAR: char = 'm';
+const GOLD_CHAR: char = '$';
 const AMULET_CHAR: char = '|';
 const POTION_CHAR: char = '!';
 const MAGIC_MAPPER_CHAR: char = '{';
@@ -54,9 +95,22 @@ lazy_static! {
     pub static ref FOREST_FLOOR_GLYPH: FontCharType = to_cp437(FOREST_FLOOR_CHAR);
     pub static ref FOREST_WALL_GLYPH: FontCharType = to_cp437(FOREST_TREE_CHAR);
     pub static ref STAIRS_GLYPH: FontCharType = to_cp437(STAIRS_CHAR);
+    pub static ref DOOR_GLYPH: FontCharType = to_cp437(DOOR_CHAR);
+    pub static ref OPEN_DOOR_GLYPH: FontCharType = to_cp437(OPEN_DOOR_CHAR);
+    pub static ref LOCKED_DOOR_GLYPH: FontCharType = to_cp437(LOCKED_DOOR_CHAR);
+    pub static ref KEY_GLYPH: FontCharType = to_cp437(KEY_CHAR);
+    pub static ref TORCH_GLYPH: FontCharType = to_cp437(TORCH_CHAR);
+    pub static ref DUNGEON_WATER_GLYPH: FontCharType = to_cp437(DUNGEON_WATER_CHAR);
+    pub static ref FOREST_WATER_GLYPH: FontCharType = to_cp437(FOREST_WATER_CHAR);
+    pub static ref LAVA_GLYPH: FontCharType = to_cp437(LAVA_CHAR);
+    pub static ref CHASM_GLYPH: FontCharType = to_cp437(CHASM_CHAR);
+    pub static ref BRIDGE_GLYPH: FontCharType = to_cp437(BRIDGE_CHAR);
     pub static ref GOBLIN_GLYPH: FontCharType = to_cp437(GOBLIN_CHAR);
     pub static ref ORC_GLYPH: FontCharType = to_cp437(ORC_CHAR);
     pub static ref PLAYER_GLYPH: FontCharType = to_cp437(PLAYER_CHAR);
+    pub static ref DOG_GLYPH: FontCharType = to_cp437(DOG_CHAR);
+    pub static ref MERCHANT_GLYPH: FontCharType = to_cp437(MERCHANT_CHAR);
+    pub static ref GOLD_GLYPH: FontCharType = to_cp437(GOLD_CHAR);
     pub static ref AMULET_GLYPH: FontCharType = to_cp437(AMULET_CHAR);
     pub static ref POTION_GLYPH: FontCharType = to_cp437(POTION_CHAR);
     pub static ref MAGIC_MAPPER_GLYPH: FontCharType = to_cp437(MAGIC_MAPPER_CHAR);
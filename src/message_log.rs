@@ -0,0 +1,18 @@
+#[derive(Clone, Debug, Default)]
+pub struct MessageLog {
+    messages: Vec<String>,
+}
+
+impl MessageLog {
+    pub fn add<S: ToString>(&mut self, message: S) {
+        self.messages.push(message.to_string());
+    }
+
+    pub fn latest(&self) -> Option<&str> {
+        self.messages.last().map(|message| message.as_str())
+    }
+
+    pub fn clear(&mut self) {
+        self.messages.clear();
+    }
+}
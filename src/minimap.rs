@@ -0,0 +1,56 @@
+use crate::prelude::*;
+
+pub const MINIMAP_SCALE: i32 = 3;
+pub const OVERVIEW_ZOOM: i32 = 2;
+
+fn priority(tile: TileType) -> i32 {
+    match tile {
+        TileType::Exit => 5,
+        TileType::Door | TileType::OpenDoor | TileType::LockedDoor(_) => 4,
+        TileType::Lava => 3,
+        TileType::DeepWater => 2,
+        TileType::Floor | TileType::Bridge => 1,
+        TileType::Wall | TileType::SecretDoor | TileType::Chasm => 0,
+    }
+}
+
+pub fn tile_color(tile: TileType) -> (u8, u8, u8) {
+    match tile {
+        TileType::Exit => CYAN,
+        TileType::Door | TileType::OpenDoor | TileType::LockedDoor(_) => BROWN1,
+        TileType::Lava => ORANGE,
+        TileType::DeepWater => BLUE,
+        TileType::Floor | TileType::Bridge => DIM_GRAY,
+        TileType::Wall | TileType::SecretDoor => GRAY,
+        TileType::Chasm => BLACK,
+    }
+}
+
+pub fn revealed_in_cell(map: &Map, cell: Point, scale: i32) -> Option<TileType> {
+    (0..scale)
+        .cartesian_product(0..scale)
+        .filter_map(|(dx, dy)| map.try_idx(Point::new(cell.x * scale + dx, cell.y * scale + dy)))
+        .filter(|idx| map.revealed_tiles[*idx])
+        .map(|idx| map.tiles[idx])
+        .max_by_key(|tile| priority(*tile))
+}
+
+#[test]
+fn minimap_cells_show_the_most_important_revealed_tile() {
+    let mut map = Map::new(6, 3);
+    assert_eq!(
+        revealed_in_cell(&map, Point::new(0, 0), MINIMAP_SCALE),
+        None
+    );
+    let exit = map.point2d_to_index(Point::new(4, 2));
+    map.tiles[exit] = TileType::Exit;
+    map.revealed_tiles.iter_mut().for_each(|t| *t = true);
+    assert_eq!(
+        revealed_in_cell(&map, Point::new(0, 0), MINIMAP_SCALE),
+        Some(TileType::Floor)
+    );
+    assert_eq!(
+        revealed_in_cell(&map, Point::new(1, 0), MINIMAP_SCALE),
+        Some(TileType::Exit)
+    );
+}
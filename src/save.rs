@@ -0,0 +1,116 @@
+use crate::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+
+const SAVE_FILE: &str = "savegame.ron";
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SavedItem {
+    pub name: String,
+    pub quantity: Option<i32>,
+    pub charges: Option<i32>,
+    pub equipped: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub config: Config,
+    pub map_level: u32,
+    pub health: i32,
+    pub max_health: i32,
+    pub gold: i32,
+    pub satiety: Option<i32>,
+    pub lantern_fuel: Option<i32>,
+    pub items: Vec<SavedItem>,
+    pub identification: Identification,
+    #[serde(default)]
+    pub statistics: Statistics,
+}
+
+impl Checkpoint {
+    pub fn capture(ecs: &World, resources: &Resources, config: &Config) -> Option<Self> {
+        let player = <(Entity, &Player)>::query()
+            .iter(ecs)
+            .find_map(|(entity, _)| Some(*entity))?;
+        let entry = ecs.entry_ref(player).ok()?;
+        let health = entry.get_component::<Health>().ok()?;
+        let items = <(Entity, &Carried)>::query()
+            .filter(component::<Item>() & !component::<Key>())
+            .iter(ecs)
+            .filter(|(_, carried)| carried.by == player)
+            .filter_map(|(item, _)| {
+                let item = ecs.entry_ref(*item).ok()?;
+                Some(SavedItem {
+                    name: item.get_component::<Name>().ok()?.0.clone(),
+                    quantity: item.get_component::<Quantity>().ok().map(|q| q.0),
+                    charges: item.get_component::<Charges>().ok().map(|c| c.current),
+                    equipped: item.get_component::<Equipped>().is_ok(),
+                })
+            })
+            .collect();
+        Some(Self {
+            config: config.clone(),
+            map_level: entry.get_component::<Player>().ok()?.map_level,
+            health: health.current,
+            max_health: health.max,
+            gold: entry.get_component::<Purse>().map_or(0, |purse| purse.0),
+            satiety: entry.get_component::<Hunger>().ok().map(|h| h.satiety),
+            lantern_fuel: entry.get_component::<Lantern>().ok().map(|l| l.fuel),
+            items,
+            identification: resources.get::<Identification>()?.clone(),
+            statistics: resources.get::<Statistics>()?.clone(),
+        })
+    }
+
+    pub fn restore(&self, ecs: &mut World, resources: &mut Resources) {
+        let player = <(Entity, &Player)>::query()
+            .iter(ecs)
+            .find_map(|(entity, _)| Some(*entity))
+            .unwrap();
+        if let Some(mut entry) = ecs.entry(player) {
+            entry.add_component(Player {
+                map_level: self.map_level,
+            });
+            entry.add_component(Health {
+                current: self.health,
+                max: self.max_health,
+            });
+            entry.add_component(Purse(self.gold));
+            if let Some(satiety) = self.satiety {
+                entry.add_component(Hunger { satiety });
+            }
+            if let Some(fuel) = self.lantern_fuel {
+                let lantern = Lantern { fuel };
+                entry.add_component(lantern.light());
+                entry.add_component(lantern);
+            }
+        }
+        spawn_inventory(ecs, player, &self.items);
+        resources.insert(self.identification.clone());
+        resources.insert(self.statistics.clone());
+    }
+
+    pub fn exists() -> bool {
+        fs::metadata(SAVE_FILE).is_ok()
+    }
+
+    pub fn load() -> Option<Self> {
+        let file = File::open(SAVE_FILE).ok()?;
+        ron::de::from_reader(file).ok()
+    }
+
+    pub fn write(&self) {
+        match ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default()) {
+            Ok(text) => {
+                if let Err(err) = fs::write(SAVE_FILE, text) {
+                    println!("Warning: could not write the save file: {}", err);
+                }
+            }
+            Err(err) => println!("Warning: could not save the game: {}", err),
+        }
+    }
+
+    pub fn delete() {
+        let _ = fs::remove_file(SAVE_FILE);
+    }
+}
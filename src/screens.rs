@@ -0,0 +1,39 @@
+use crate::prelude::*;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct InventoryScreen {
+    pub selected: usize,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct LookScreen {
+    pub cursor: Option<Point>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct OverviewScreen {
+    pub offset: Option<Point>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TradeMode {
+    Buy,
+    Sell,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TradeScreen {
+    pub merchant: Option<Entity>,
+    pub mode: TradeMode,
+    pub selected: usize,
+}
+
+impl Default for TradeScreen {
+    fn default() -> Self {
+        Self {
+            merchant: None,
+            mode: TradeMode::Buy,
+            selected: 0,
+        }
+    }
+}
@@ -5,9 +5,15 @@ use template::Templates;
 
 const PLAYER_MAX_HEALTH: i32 = 10;
 const PLAYER_SIGHT_DISTANCE: i32 = 8;
+const COMPANION_MAX_HEALTH: i32 = 8;
+const COMPANION_SIGHT_DISTANCE: i32 = 6;
+const MERCHANT_MAX_HEALTH: i32 = 20;
+const MERCHANT_STOCK_SIZE: usize = 6;
+const NUM_TORCHES: usize = 12;
+const TORCH_RADIUS: i32 = 4;
 //const MONSTER_SIGHT_DISTANCE: i32 = 6;
-pub fn spawn_player(ecs: &mut World, pos: Point) {
-    ecs.push((
+pub fn spawn_player(ecs: &mut World, pos: Point) -> Entity {
+    let player = ecs.push((
         Player { map_level: 0 },
         pos,
         Render {
@@ -20,9 +26,135 @@ pub fn spawn_player(ecs: &mut World, pos: Point) {
         },
         FieldOfView::new(PLAYER_SIGHT_DISTANCE),
         Damage(1),
+        Energy::new(NORMAL_SPEED),
+        Faction::Player,
+    ));
+    let mut entry = ecs.entry(player).unwrap();
+    entry.add_component(Purse(0));
+    entry.add_component(Regeneration::default());
+    entry.add_component(Lantern::default());
+    entry.add_component(Lantern::default().light());
+    player
+}
+
+pub fn spawn_companion(ecs: &mut World, map: &Map, player_pos: Point) {
+    let companion = ecs.push((
+        Companion,
+        FollowingPlayer,
+        companion_start(map, player_pos),
+        Render {
+            color: ColorPair::new(WHITE, BLACK),
+            glyph: *DOG_GLYPH,
+        },
+        Name(String::from("Dog")),
+        Health {
+            current: COMPANION_MAX_HEALTH,
+            max: COMPANION_MAX_HEALTH,
+        },
+        FieldOfView::new(COMPANION_SIGHT_DISTANCE),
+        Damage(1),
+    ));
+    let mut entry = ecs.entry(companion).unwrap();
+    entry.add_component(Energy::new(NORMAL_SPEED));
+    entry.add_component(Faction::Player);
+}
+
+pub fn companion_start(map: &Map, player_pos: Point) -> Point {
+    [
+        Point::new(-1, 0),
+        Point::new(1, 0),
+        Point::new(0, -1),
+        Point::new(0, 1),
+        Point::new(-1, -1),
+        Point::new(1, -1),
+        Point::new(-1, 1),
+        Point::new(1, 1),
+    ]
+    .iter()
+    .map(|delta| player_pos + *delta)
+    .find(|pos| map.is_safe_floor(*pos))
+    .unwrap_or(player_pos)
+}
+
+pub fn spawn_merchant(ecs: &mut World, rng: &mut RandomNumberGenerator, level: usize, pos: Point) {
+    let merchant = ecs.push((
+        Merchant,
+        pos,
+        Render {
+            color: ColorPair::new(WHITE, BLACK),
+            glyph: *MERCHANT_GLYPH,
+        },
+        Name(String::from("Merchant")),
+        Health {
+            current: MERCHANT_MAX_HEALTH,
+            max: MERCHANT_MAX_HEALTH,
+        },
+        Faction::Townsfolk,
+    ));
+    Templates::load().spawn_merchant_stock(ecs, rng, level, merchant, MERCHANT_STOCK_SIZE);
+}
+
+pub fn spawn_gold(commands: &mut CommandBuffer, pos: Point, amount: i32) {
+    commands.push((
+        Item,
+        Gold(amount),
+        pos,
+        Render {
+            color: ColorPair::new(WHITE, BLACK),
+            glyph: *GOLD_GLYPH,
+        },
+        Name(String::from("Gold")),
     ));
 }
 
+pub fn spawn_key(ecs: &mut World, pos: Point, id: u8) {
+    ecs.push((
+        Item,
+        Key(id),
+        pos,
+        Render {
+            color: ColorPair::new(WHITE, BLACK),
+            glyph: *KEY_GLYPH,
+        },
+        Name(String::from("Brass Key")),
+    ));
+}
+
+pub fn spawn_torches(ecs: &mut World, rng: &mut RandomNumberGenerator, map: &Map) {
+    let mut walls: Vec<Point> = (0..map.tiles.len())
+        .filter(|idx| map.tiles[*idx] == TileType::Wall)
+        .map(|idx| map.index_to_point2d(idx))
+        .filter(|pt| {
+            [
+                Point::new(-1, 0),
+                Point::new(1, 0),
+                Point::new(0, -1),
+                Point::new(0, 1),
+            ]
+            .iter()
+            .any(|delta| map.is_safe_floor(*pt + *delta))
+        })
+        .collect();
+    for _ in 0..NUM_TORCHES {
+        if walls.is_empty() {
+            break;
+        }
+        let pos = walls.remove(rng.random_slice_index(&walls).unwrap());
+        ecs.push((
+            Torch,
+            pos,
+            Render {
+                color: ColorPair::new(ORANGE, BLACK),
+                glyph: *TORCH_GLYPH,
+            },
+            Name(String::from("Torch")),
+            LightSource {
+                radius: TORCH_RADIUS,
+            },
+        ));
+    }
+}
+
 pub fn spawn_amulet_of_yala(ecs: &mut World, pos: Point) {
     ecs.push((
         Item,
@@ -45,3 +177,15 @@ pub fn spawn_level(
     let template = Templates::load();
     template.spawn_entities(ecs, rng, level, spawn_points);
 }
+
+pub fn spawn_inventory(ecs: &mut World, carrier: Entity, items: &[SavedItem]) {
+    Templates::load().spawn_inventory(ecs, carrier, items);
+}
+
+pub fn spawn_item(commands: &mut CommandBuffer, name: &str, pos: Point) {
+    Templates::load().spawn_item(name, pos, commands);
+}
+
+pub fn unidentified_items() -> Vec<(String, AppearanceKind)> {
+    Templates::load().unidentified_items()
+}
@@ -17,16 +17,27 @@ pub struct Template {
     pub levels: HashSet<usize>,
     pub frequency: i32,
     pub name: String,
+    pub description: Option<String>,
     pub glyph: char,
     pub provides: Option<Vec<(String, i32)>>,
     pub hp: Option<i32>,
     pub base_damage: Option<i32>,
+    pub speed: Option<i32>,
+    pub faction: Option<Faction>,
+    pub stackable: Option<bool>,
+    pub charges: Option<i32>,
+    pub appearance: Option<AppearanceKind>,
+    pub value: Option<i32>,
+    pub gold: Option<i32>,
+    pub trap: Option<TrapKind>,
+    pub light: Option<i32>,
 }
 
 #[derive(Clone, Deserialize, Debug, PartialEq)]
 pub enum EntityType {
     Enemy,
     Item,
+    Trap,
 }
 
 impl Templates {
@@ -45,6 +56,13 @@ impl Templates {
             .expect("Unable to load templates")
     }
 
+    pub fn unidentified_items(&self) -> Vec<(String, AppearanceKind)> {
+        self.entities
+            .iter()
+            .filter_map(|t| t.appearance.map(|kind| (t.name.clone(), kind)))
+            .collect()
+    }
+
     pub fn spawn_entities(
         &self,
         ecs: &mut World,
@@ -52,26 +70,99 @@ impl Templates {
         level: usize,
         spawn_points: &[Point],
     ) {
+        let available_entities = self.available_entities(level, |_| true);
+
+        let mut commands = CommandBuffer::new(ecs);
+        spawn_points.iter().for_each(|pt| {
+            if let Some(entity) = rng.random_slice_entry(&available_entities) {
+                self.spawn_entity(pt, entity, &mut commands);
+            }
+        });
+        commands.flush(ecs);
+    }
+
+    pub fn spawn_merchant_stock(
+        &self,
+        ecs: &mut World,
+        rng: &mut RandomNumberGenerator,
+        level: usize,
+        merchant: Entity,
+        stock_size: usize,
+    ) {
+        let available_entities = self.available_entities(level, |t| {
+            t.entity_type == EntityType::Item && t.value.is_some()
+        });
+
+        let mut commands = CommandBuffer::new(ecs);
+        for _ in 0..stock_size {
+            if let Some(template) = rng.random_slice_entry(&available_entities) {
+                let item = self.spawn_entity(&Point::zero(), template, &mut commands);
+                commands.remove_component::<Point>(item);
+                commands.add_component(item, Carried { by: merchant });
+            }
+        }
+        commands.flush(ecs);
+    }
+
+    pub fn spawn_inventory(&self, ecs: &mut World, carrier: Entity, items: &[SavedItem]) {
+        let mut commands = CommandBuffer::new(ecs);
+        items.iter().for_each(|saved| {
+            if let Some(template) = self.entities.iter().find(|t| t.name == saved.name) {
+                let item = self.spawn_entity(&Point::zero(), template, &mut commands);
+                commands.remove_component::<Point>(item);
+                commands.add_component(item, Carried { by: carrier });
+                if let Some(quantity) = saved.quantity {
+                    commands.add_component(item, Quantity(quantity));
+                }
+                if let Some(current) = saved.charges {
+                    commands.add_component(
+                        item,
+                        Charges {
+                            current,
+                            max: template.charges.unwrap_or(current),
+                        },
+                    );
+                }
+                if saved.equipped {
+                    commands.add_component(item, Equipped);
+                }
+            }
+        });
+        commands.flush(ecs);
+    }
+
+    pub fn spawn_item(&self, name: &str, pos: Point, commands: &mut CommandBuffer) {
+        if let Some(template) = self
+            .entities
+            .iter()
+            .find(|t| t.entity_type == EntityType::Item && t.name == name)
+        {
+            self.spawn_entity(&pos, template, commands);
+        }
+    }
+
+    fn available_entities<F>(&self, level: usize, wanted: F) -> Vec<&Template>
+    where
+        F: Fn(&Template) -> bool,
+    {
         let mut available_entities = Vec::new();
         self.entities
             .iter()
-            .filter(|e| e.levels.contains(&level))
+            .filter(|e| e.levels.contains(&level) && wanted(e))
             .for_each(|t| {
                 for _ in 0..t.frequency {
                     available_entities.push(t)
                 }
             });
-
-        let mut commands = CommandBuffer::new(ecs);
-        spawn_points.iter().for_each(|pt| {
-            if let Some(entity) = rng.random_slice_entry(&available_entities) {
-                self.spawn_entity(pt, entity, &mut commands)
-            }
-        });
-        commands.flush(ecs);
+        available_entities
     }
 
-    fn spawn_entity(&self, pt: &Point, template: &Template, commands: &mut CommandBuffer) {
+    fn spawn_entity(
+        &self,
+        pt: &Point,
+        template: &Template,
+        commands: &mut CommandBuffer,
+    ) -> Entity {
         let entity = commands.push((
             pt.clone(),
             Render {
@@ -83,10 +174,21 @@ impl Templates {
 
         match template.entity_type {
             EntityType::Item => commands.add_component(entity, Item {}),
+            EntityType::Trap => {
+                commands.add_component(
+                    entity,
+                    Trap {
+                        kind: template.trap.expect("Trap template without a trap kind"),
+                    },
+                );
+                commands.add_component(entity, Hidden);
+            }
             EntityType::Enemy => {
                 commands.add_component(entity, Enemy {});
                 commands.add_component(entity, FieldOfView::new(6));
                 commands.add_component(entity, ChasingPlayer {});
+                commands.add_component(entity, Energy::new(template.speed.unwrap_or(NORMAL_SPEED)));
+                commands.add_component(entity, template.faction.unwrap_or(Faction::Monsters));
                 commands.add_component(
                     entity,
                     Health {
@@ -103,15 +205,54 @@ impl Templates {
                 .for_each(|(provides, n)| match provides.as_str() {
                     "Healing" => commands.add_component(entity, ProvidesHealing { amount: *n }),
                     "MagicMap" => commands.add_component(entity, ProvidesDungeonMap {}),
+                    "Charm" => commands.add_component(entity, ProvidesCharm {}),
+                    "Identify" => commands.add_component(entity, ProvidesIdentify {}),
+                    "Nutrition" => commands.add_component(entity, ProvidesNutrition { amount: *n }),
+                    "Digging" => commands.add_component(entity, ProvidesDigging {}),
+                    "Blast" => commands.add_component(entity, ProvidesBlast { radius: *n }),
+                    "Fire" => commands.add_component(entity, ProvidesFire { radius: *n }),
+                    "Gas" => commands.add_component(entity, ProvidesGas { radius: *n }),
+                    "Fuel" => commands.add_component(entity, ProvidesFuel { amount: *n }),
                     _ => println!("Warning: we don't know how to provide {}", provides),
                 })
         }
 
+        if let Some(description) = &template.description {
+            commands.add_component(entity, Description(description.clone()));
+        }
+
+        if template.stackable == Some(true) {
+            commands.add_component(entity, Quantity(1));
+        }
+
+        if let Some(charges) = template.charges {
+            commands.add_component(
+                entity,
+                Charges {
+                    current: charges,
+                    max: charges,
+                },
+            );
+        }
+
+        if let Some(value) = template.value {
+            commands.add_component(entity, Value(value));
+        }
+
+        if let Some(gold) = template.gold {
+            commands.add_component(entity, DropsGold(gold));
+        }
+
+        if let Some(radius) = template.light {
+            commands.add_component(entity, LightSource { radius });
+        }
+
         if let Some(damage) = &template.base_damage {
             commands.add_component(entity, Damage(*damage));
             if template.entity_type == EntityType::Item {
                 commands.add_component(entity, Weapon {})
             }
         }
+        entity
     }
 }
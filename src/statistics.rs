@@ -0,0 +1,29 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Statistics {
+    pub turns: u32,
+    pub kills: BTreeMap<String, u32>,
+}
+
+impl Statistics {
+    pub fn record_kill(&mut self, name: &str) {
+        *self.kills.entry(String::from(name)).or_insert(0) += 1;
+    }
+
+    pub fn total_kills(&self) -> u32 {
+        self.kills.values().sum()
+    }
+}
+
+#[test]
+fn kills_are_counted_by_monster_type() {
+    let mut statistics = Statistics::default();
+    statistics.record_kill("Goblin");
+    statistics.record_kill("Orc");
+    statistics.record_kill("Goblin");
+    assert_eq!(statistics.kills["Goblin"], 2);
+    assert_eq!(statistics.kills["Orc"], 1);
+    assert_eq!(statistics.total_kills(), 3);
+}
@@ -0,0 +1,191 @@
+use crate::prelude::*;
+
+const LEFT_COLUMN: i32 = 4;
+const RIGHT_COLUMN: i32 = 48;
+const TOP: i32 = 4;
+
+#[system]
+#[read_component(Player)]
+#[read_component(Point)]
+#[read_component(Health)]
+#[read_component(Damage)]
+#[read_component(Item)]
+#[read_component(Carried)]
+#[read_component(Equipped)]
+#[read_component(Name)]
+#[read_component(Quantity)]
+#[read_component(Charges)]
+#[read_component(Purse)]
+#[read_component(Hunger)]
+#[read_component(Lantern)]
+#[read_component(LightSource)]
+pub fn character(
+    ecs: &SubWorld,
+    #[resource] key: &Option<VirtualKeyCode>,
+    #[resource] turn_state: &mut TurnState,
+    #[resource] bindings: &KeyBindings,
+    #[resource] identification: &Identification,
+    #[resource] statistics: &Statistics,
+    #[resource] environment: &Environment,
+    #[resource] map: &Map,
+) {
+    if let Some(key) = key {
+        if *key == VirtualKeyCode::Escape || bindings.action(*key) == Some(Action::CharacterSheet) {
+            *turn_state = TurnState::AwaitingInput;
+            return;
+        }
+    }
+
+    let (player, pos, player_info, health) = <(Entity, &Point, &Player, &Health)>::query()
+        .iter(ecs)
+        .find_map(|(entity, pos, player, health)| Some((*entity, *pos, *player, *health)))
+        .unwrap();
+    let entry = ecs.entry_ref(player).unwrap();
+    let base_damage = entry.get_component::<Damage>().map_or(0, |damage| damage.0);
+    let hunger = entry
+        .get_component::<Hunger>()
+        .ok()
+        .map(|hunger| hunger.state());
+    let hunger_penalty = hunger.map_or(0, HungerState::attack_penalty);
+
+    let mut draw_batch = DrawBatch::new();
+    draw_batch.target(HUD_LAYER.id);
+    draw_batch.print_color_centered(1, "Character", ColorPair::new(YELLOW, BLACK));
+
+    let mut y = TOP;
+    let mut line = |draw_batch: &mut DrawBatch, text: String, color: (u8, u8, u8)| {
+        draw_batch.print_color(
+            Point::new(LEFT_COLUMN, y),
+            text,
+            ColorPair::new(color, BLACK),
+        );
+        y += 1;
+    };
+    line(
+        &mut draw_batch,
+        format!("Health: {} / {}", health.current, health.max),
+        WHITE,
+    );
+    line(
+        &mut draw_batch,
+        format!("Dungeon level: {}", player_info.map_level + 1),
+        WHITE,
+    );
+    line(
+        &mut draw_batch,
+        format!("Turns: {}", statistics.turns),
+        WHITE,
+    );
+    line(
+        &mut draw_batch,
+        format!(
+            "Gold: {}",
+            entry.get_component::<Purse>().map_or(0, |purse| purse.0)
+        ),
+        GOLD,
+    );
+    line(&mut draw_batch, String::new(), WHITE);
+    line(&mut draw_batch, String::from("Attack"), YELLOW);
+    line(
+        &mut draw_batch,
+        format!("  Base damage: {}", base_damage),
+        WHITE,
+    );
+    let mut weapon_damage = 0;
+    <(Entity, &Carried)>::query()
+        .filter(component::<Item>() & component::<Equipped>())
+        .iter(ecs)
+        .filter(|(_, carried)| carried.by == player)
+        .for_each(|(item, _)| {
+            let item = ecs.entry_ref(*item).unwrap();
+            let damage = item.get_component::<Damage>().map_or(0, |damage| damage.0);
+            weapon_damage += damage;
+            line(
+                &mut draw_batch,
+                format!("  {}: {:+}", item_label(&item, identification), damage),
+                WHITE,
+            );
+        });
+    if hunger_penalty > 0 {
+        line(
+            &mut draw_batch,
+            format!("  Hunger: {:+}", -hunger_penalty),
+            ORANGE,
+        );
+    }
+    line(
+        &mut draw_batch,
+        format!(
+            "  Total: {}",
+            i32::max(0, base_damage + weapon_damage - hunger_penalty)
+        ),
+        GREEN,
+    );
+
+    line(&mut draw_batch, String::new(), WHITE);
+    line(&mut draw_batch, String::from("Effects"), YELLOW);
+    let mut effects = Vec::new();
+    match hunger {
+        Some(HungerState::Hungry) => effects.push((String::from("Hungry"), YELLOW)),
+        Some(HungerState::Weak) => effects.push((String::from("Weak from hunger"), ORANGE)),
+        Some(HungerState::Fainting) => effects.push((String::from("Fainting from hunger"), RED)),
+        _ => (),
+    }
+    if let (Ok(lantern), Ok(light)) = (
+        entry.get_component::<Lantern>(),
+        entry.get_component::<LightSource>(),
+    ) {
+        let color = if lantern.fuel < LOW_LANTERN_FUEL {
+            YELLOW
+        } else {
+            WHITE
+        };
+        effects.push((
+            format!(
+                "Lantern lighting {} tiles, {} turns of oil left",
+                light.radius, lantern.fuel
+            ),
+            color,
+        ));
+    }
+    if let Some(idx) = map.try_idx(pos) {
+        if environment.fire[idx] > 0 {
+            effects.push((String::from("Burning"), RED));
+        }
+        if environment.gas[idx] >= HARMFUL_GAS {
+            effects.push((String::from("Choking on poison gas"), GREEN));
+        }
+    }
+    if effects.is_empty() {
+        effects.push((String::from("None"), GRAY));
+    }
+    effects
+        .into_iter()
+        .for_each(|(effect, color)| line(&mut draw_batch, format!("  {}", effect), color));
+
+    draw_batch.print_color(
+        Point::new(RIGHT_COLUMN, TOP),
+        format!("Kills: {}", statistics.total_kills()),
+        ColorPair::new(YELLOW, BLACK),
+    );
+    statistics
+        .kills
+        .iter()
+        .enumerate()
+        .for_each(|(n, (name, count))| {
+            draw_batch.print(
+                Point::new(RIGHT_COLUMN, TOP + 1 + n as i32),
+                format!("  {} x{}", name, count),
+            );
+        });
+
+    draw_batch.print_color_centered(
+        y + 2,
+        format!(
+            "{} or Escape to close.",
+            bindings.describe(Action::CharacterSheet)
+        ),
+        ColorPair::new(GREEN, BLACK),
+    );
+    draw_batch.submit(HUD_LAYER.z_order).expect("Batch error");
+}
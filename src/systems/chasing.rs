@@ -3,59 +3,112 @@ use core::fmt;
 use std::collections::HashSet;
 use std::fmt::{Debug, Display, Formatter};
 
+const ATTACK_DISTANCE: f32 = 1.2;
+const DIAGONAL_ATTACK_DISTANCE: f32 = 1.5;
+const FOLLOW_DISTANCE: f32 = 2.0;
+
 #[system]
 #[read_component(Point)]
 #[read_component(ChasingPlayer)]
+#[read_component(FollowingPlayer)]
 #[read_component(FieldOfView)]
 #[read_component(Health)]
 #[read_component(Player)]
-pub fn chasing(#[resource] map: &Map, ecs: &SubWorld, commands: &mut CommandBuffer) {
-    let mut movers = <(Entity, &Point, &ChasingPlayer, &FieldOfView)>::query();
-    let mut positions = <(Entity, &Point, &Health)>::query();
+#[read_component(Energy)]
+#[read_component(Faction)]
+#[read_component(Trap)]
+pub fn chasing(
+    #[state] cache: &mut PlayerDistances,
+    #[resource] map: &Map,
+    ecs: &SubWorld,
+    commands: &mut CommandBuffer,
+) {
+    let mut movers = <(Entity, &Point, &FieldOfView, &Energy, &Faction)>::query()
+        .filter(component::<ChasingPlayer>() | component::<FollowingPlayer>());
+    let mut creatures = <(Entity, &Point, &Health, &Faction)>::query();
 
-    let player_pos = <(&Point, &Player)>::query().iter(ecs).nth(0).unwrap().0;
+    let (player, player_pos) = <(Entity, &Point)>::query()
+        .filter(component::<Player>())
+        .iter(ecs)
+        .find_map(|(entity, pos)| Some((*entity, *pos)))
+        .unwrap();
     let player_idx = map.index_for(player_pos.x, player_pos.y);
 
-    let search_targets = vec![player_idx];
-    let dijkstra_map = DijkstraMap::new(
-        map.width,
-        map.height,
-        &search_targets,
-        map,
-        DISTANCE_MAX_DEPTH,
-    );
+    let monster_traps: HashSet<usize> = <&Point>::query()
+        .filter(component::<Trap>())
+        .iter(ecs)
+        .map(|pos| map.point2d_to_index(*pos))
+        .collect();
+    let known_traps = |faction: &Faction| {
+        if *faction == Faction::Player {
+            &map.hazards
+        } else {
+            &monster_traps
+        }
+    };
+    let (monster_distances, ally_distances) = cache.get(map, player_idx, &monster_traps);
+    let towards_player = |pos: &Point, faction: &Faction| {
+        let dijkstra_map = if *faction == Faction::Player {
+            ally_distances
+        } else {
+            monster_distances
+        };
+        let pathing = TrapAwareMap::new(map, known_traps(faction));
+        DijkstraMap::find_lowest_exit(dijkstra_map, map.point2d_to_index(*pos), &pathing)
+            .map(|idx| map.index_to_point2d(idx))
+    };
 
+    let attack_distance = if map.allow_diagonals {
+        DIAGONAL_ATTACK_DISTANCE
+    } else {
+        ATTACK_DISTANCE
+    };
     let mut requested_destinations = HashSet::new();
     movers
         .iter(ecs)
-        .filter(|(_, _, _, fov)| fov.is_visible(&player_pos))
-        .for_each(|(entity, pos, _, _)| {
-            let idx = map.index_for(pos.x, pos.y);
-            if let Some(destination) = DijkstraMap::find_lowest_exit(&dijkstra_map, idx, map) {
-                let distance = DistanceAlg::Pythagoras.distance2d(*pos, *player_pos);
-                let destination = if distance > 1.2 {
-                    map.index_to_point2d(destination)
-                } else {
-                    *player_pos
-                };
-                let attacked = positions
-                    .iter(ecs)
-                    .filter(|(victim, target_pos, _)| {
-                        **target_pos == destination && victim_is_player(victim, ecs)
-                    })
-                    .inspect(|(victim, _, _)| {
-                        commands.push((
-                            (),
-                            WantsToAttack {
-                                attacker: *entity,
-                                victim: **victim,
-                            },
-                        ));
-                    })
-                    .count()
-                    > 0;
+        .filter(|(_, _, _, energy, _)| energy.can_act())
+        .for_each(|(entity, pos, fov, _, faction)| {
+            let target = creatures
+                .iter(ecs)
+                .filter(|(victim, target_pos, _, victim_faction)| {
+                    **victim != *entity
+                        && faction.is_hostile_to(**victim_faction)
+                        && fov.is_visible(target_pos)
+                })
+                .map(|(victim, target_pos, _, _)| {
+                    (
+                        *victim,
+                        *target_pos,
+                        DistanceAlg::Pythagoras.distance2d(*pos, *target_pos),
+                    )
+                })
+                .min_by(|(_, _, a), (_, _, b)| a.partial_cmp(b).unwrap());
+
+            let destination = match target {
+                Some((victim, target_pos, distance))
+                    if distance < attack_distance && map.can_step(*pos, target_pos - *pos) =>
+                {
+                    commands.push((
+                        (),
+                        WantsToAttack {
+                            attacker: *entity,
+                            victim,
+                        },
+                    ));
+                    None
+                }
+                Some((victim, _, _)) if victim == player => towards_player(pos, faction),
+                Some((_, target_pos, _)) => next_step(map, known_traps(faction), *pos, target_pos),
+                None if is_following(entity, ecs)
+                    && DistanceAlg::Pythagoras.distance2d(*pos, player_pos) > FOLLOW_DISTANCE =>
+                {
+                    towards_player(pos, faction)
+                }
+                None => None,
+            };
 
-                if !attacked && !requested_destinations.contains(&destination) {
+            if let Some(destination) = destination {
+                if !requested_destinations.contains(&destination) {
                     requested_destinations.insert(destination);
                     commands.push((
                         (),
@@ -69,10 +122,59 @@ pub fn chasing(#[resource] map: &Map, ecs: &SubWorld, commands: &mut CommandBuff
         });
 }
 
-fn victim_is_player(victim: &Entity, ecs: &SubWorld) -> bool {
-    ecs.entry_ref(*victim)
+#[derive(Default)]
+pub struct PlayerDistances {
+    key: Option<(usize, u64, usize)>,
+    monsters: Option<DijkstraMap>,
+    allies: Option<DijkstraMap>,
+}
+
+impl PlayerDistances {
+    fn get(
+        &mut self,
+        map: &Map,
+        player_idx: usize,
+        monster_traps: &HashSet<usize>,
+    ) -> (&DijkstraMap, &DijkstraMap) {
+        let key = Some((player_idx, map.revision, monster_traps.len()));
+        if self.key != key || self.monsters.is_none() || self.allies.is_none() {
+            self.key = key;
+            let distances = |traps| {
+                DijkstraMap::new(
+                    map.width,
+                    map.height,
+                    &[player_idx],
+                    &TrapAwareMap::new(map, traps),
+                    DISTANCE_MAX_DEPTH,
+                )
+            };
+            self.monsters = Some(distances(monster_traps));
+            self.allies = Some(distances(&map.hazards));
+        }
+        (
+            self.monsters.as_ref().unwrap(),
+            self.allies.as_ref().unwrap(),
+        )
+    }
+}
+
+fn next_step(map: &Map, traps: &HashSet<usize>, from: Point, to: Point) -> Option<Point> {
+    let path = a_star_search(
+        map.point2d_to_index(from),
+        map.point2d_to_index(to),
+        &TrapAwareMap::new(map, traps),
+    );
+    if path.success && path.steps.len() > 1 {
+        Some(map.index_to_point2d(path.steps[1]))
+    } else {
+        None
+    }
+}
+
+fn is_following(entity: &Entity, ecs: &SubWorld) -> bool {
+    ecs.entry_ref(*entity)
         .unwrap()
-        .get_component::<Player>()
+        .get_component::<FollowingPlayer>()
         .is_ok()
 }
 
@@ -6,6 +6,12 @@ use crate::prelude::*;
 #[write_component(Health)]
 #[read_component(Damage)]
 #[read_component(Carried)]
+#[read_component(Equipped)]
+#[read_component(DropsGold)]
+#[read_component(Name)]
+#[read_component(Enemy)]
+#[read_component(Point)]
+#[read_component(Hunger)]
 pub fn combat(ecs: &mut SubWorld, commands: &mut CommandBuffer) {
     let mut attackers = <(Entity, &WantsToAttack)>::query();
     let victims: Vec<(Entity, Entity, Entity)> = attackers
@@ -14,12 +20,6 @@ pub fn combat(ecs: &mut SubWorld, commands: &mut CommandBuffer) {
         .collect();
 
     victims.iter().for_each(|(message, attacker, victim)| {
-        let is_player = ecs
-            .entry_ref(*victim)
-            .unwrap()
-            .get_component::<Player>()
-            .is_ok();
-
         let base_damage = if let Ok(a) = ecs.entry_ref(*attacker) {
             if let Ok(dmg) = a.get_component::<Damage>() {
                 dmg.0
@@ -31,22 +31,73 @@ pub fn combat(ecs: &mut SubWorld, commands: &mut CommandBuffer) {
         };
 
         let weapon_damage: i32 = <(&Carried, &Damage)>::query()
+            .filter(component::<Equipped>())
             .iter(ecs)
             .filter(|(carried, _)| carried.by == *attacker)
             .map(|(_, dmg)| dmg.0)
             .sum();
-        let final_damage = base_damage + weapon_damage;
+        let hunger_state = |entity: Entity| {
+            ecs.entry_ref(entity)
+                .ok()
+                .and_then(|entry| entry.get_component::<Hunger>().ok().map(Hunger::state))
+        };
+        let hunger_penalty = hunger_state(*attacker).map_or(0, HungerState::attack_penalty)
+            - hunger_state(*victim).map_or(0, HungerState::defense_penalty);
+        let final_damage = i32::max(0, base_damage + weapon_damage - hunger_penalty);
 
-        if let Ok(mut health) = ecs
-            .entry_mut(*victim)
-            .unwrap()
-            .get_component_mut::<Health>()
-        {
-            health.current -= final_damage;
-            if health.current < 1 && !is_player {
-                commands.remove(*victim)
-            }
-        }
+        damage(ecs, commands, *victim, final_damage, Some(*attacker));
         commands.remove(*message);
     })
 }
+
+pub fn damage(
+    ecs: &mut SubWorld,
+    commands: &mut CommandBuffer,
+    victim: Entity,
+    amount: i32,
+    killer: Option<Entity>,
+) {
+    let is_player = ecs
+        .entry_ref(victim)
+        .map_or(false, |entry| entry.get_component::<Player>().is_ok());
+    let dead = match ecs.entry_mut(victim) {
+        Ok(mut entry) => match entry.get_component_mut::<Health>() {
+            Ok(health) => {
+                health.current = i32::min(health.max, health.current - amount);
+                health.current < 1 && !is_player
+            }
+            Err(_) => false,
+        },
+        Err(_) => false,
+    };
+    if dead {
+        slay(ecs, commands, victim, killer);
+    }
+}
+
+pub fn slay(ecs: &SubWorld, commands: &mut CommandBuffer, victim: Entity, killer: Option<Entity>) {
+    let by_player = killer
+        .and_then(|killer| ecs.entry_ref(killer).ok())
+        .map_or(false, |killer| killer.get_component::<Player>().is_ok());
+    if let Ok(entry) = ecs.entry_ref(victim) {
+        if let (Ok(gold), Ok(pos)) = (
+            entry.get_component::<DropsGold>(),
+            entry.get_component::<Point>(),
+        ) {
+            spawn_gold(commands, *pos, gold.0);
+        }
+        if let (Ok(name), Ok(_), true) = (
+            entry.get_component::<Name>(),
+            entry.get_component::<Enemy>(),
+            by_player,
+        ) {
+            commands.push((
+                (),
+                Slain {
+                    name: name.0.clone(),
+                },
+            ));
+        }
+    }
+    commands.remove(victim);
+}
@@ -5,18 +5,20 @@ use crate::prelude::*;
 #[read_component(Player)]
 #[read_component(Point)]
 #[read_component(AmuletOfYala)]
-pub fn end_turn(ecs: &SubWorld, #[resource] turn_state: &mut TurnState, #[resource] map: &Map) {
-    let mut player_hp = <(&Health, &Point)>::query().filter(component::<Player>());
-    let mut amulet = <&Point>::query().filter(component::<AmuletOfYala>());
-    let amulet_default = Point::new(-1, -1);
-    let amulet_pos = amulet.iter(ecs).nth(0).unwrap_or(&amulet_default);
+#[read_component(Trap)]
+#[write_component(Energy)]
+pub fn end_turn(ecs: &mut SubWorld, #[resource] turn_state: &mut TurnState, #[resource] map: &Map) {
     let mut new_state = match turn_state {
         TurnState::AwaitingInput => return,
         TurnState::PlayerTurn => TurnState::MonsterTurn,
-        TurnState::MonsterTurn => TurnState::AwaitingInput,
+        TurnState::MonsterTurn => next_actor(ecs),
         _ => turn_state.clone(),
     };
 
+    let mut player_hp = <(&Health, &Point)>::query().filter(component::<Player>());
+    let mut amulet = <&Point>::query().filter(component::<AmuletOfYala>());
+    let amulet_default = Point::new(-1, -1);
+    let amulet_pos = amulet.iter(ecs).nth(0).unwrap_or(&amulet_default);
     player_hp.iter(ecs).for_each(|(hp, pos)| {
         if hp.current < 1 {
             new_state = TurnState::GameOver;
@@ -25,9 +27,44 @@ pub fn end_turn(ecs: &SubWorld, #[resource] turn_state: &mut TurnState, #[resour
             new_state = TurnState::Victory;
         }
         let idx = map.point2d_to_index(*pos);
-        if map.tiles[idx] == TileType::Exit {
+        if map.tiles[idx] == TileType::Exit || on_trapdoor(ecs, *pos) {
             new_state = TurnState::NextLevel;
         }
     });
     *turn_state = new_state;
 }
+
+fn player_ready(ecs: &SubWorld) -> bool {
+    <&Energy>::query()
+        .filter(component::<Player>())
+        .iter(ecs)
+        .any(Energy::can_act)
+}
+
+fn next_actor(ecs: &mut SubWorld) -> TurnState {
+    if player_ready(ecs) {
+        return TurnState::AwaitingInput;
+    }
+    if <&Energy>::query()
+        .filter(!component::<Player>())
+        .iter(ecs)
+        .any(Energy::can_act)
+    {
+        return TurnState::MonsterTurn;
+    }
+
+    <&mut Energy>::query()
+        .iter_mut(ecs)
+        .for_each(|energy| energy.recharge());
+    if player_ready(ecs) {
+        TurnState::AwaitingInput
+    } else {
+        TurnState::MonsterTurn
+    }
+}
+
+fn on_trapdoor(ecs: &SubWorld, pos: Point) -> bool {
+    <(&Trap, &Point)>::query()
+        .iter(ecs)
+        .any(|(trap, trap_pos)| trap.kind == TrapKind::Trapdoor && *trap_pos == pos)
+}
@@ -0,0 +1,75 @@
+use crate::prelude::*;
+
+const WATER_COST_MULTIPLIER: i32 = 2;
+
+#[system]
+#[read_component(Player)]
+#[read_component(Point)]
+#[write_component(Energy)]
+pub fn spend_energy(ecs: &mut SubWorld, #[resource] turn_state: &TurnState, #[resource] map: &Map) {
+    let cost = |pos: Option<&Point>| match pos.and_then(|pos| map.try_idx(*pos)) {
+        Some(idx) if map.tiles[idx] == TileType::DeepWater => ACTION_COST * WATER_COST_MULTIPLIER,
+        _ => ACTION_COST,
+    };
+    match turn_state {
+        TurnState::PlayerTurn => <(&mut Energy, Option<&Point>)>::query()
+            .filter(component::<Player>())
+            .iter_mut(ecs)
+            .for_each(|(energy, pos)| energy.spend_cost(cost(pos))),
+        TurnState::MonsterTurn => <(&mut Energy, Option<&Point>)>::query()
+            .filter(!component::<Player>())
+            .iter_mut(ecs)
+            .for_each(|(energy, pos)| energy.spend_cost(cost(pos))),
+        _ => (),
+    }
+}
+
+#[test]
+fn speed_two_monster_acts_twice_per_player_move() {
+    let mut ecs = World::default();
+    let mut resources = Resources::default();
+    resources.insert(Map::new(10, 10));
+    resources.insert(TurnState::AwaitingInput);
+    ecs.push((
+        Player { map_level: 0 },
+        Point::new(1, 1),
+        Health {
+            current: 10,
+            max: 10,
+        },
+        Energy::new(NORMAL_SPEED),
+    ));
+    let monster = ecs.push((Enemy, Point::new(5, 5), Energy::new(NORMAL_SPEED * 2)));
+    let mut schedule = Schedule::builder()
+        .add_system(spend_energy_system())
+        .flush()
+        .add_system(super::end_turn::end_turn_system())
+        .build();
+    let monster_can_act = |ecs: &World| {
+        ecs.entry_ref(monster)
+            .unwrap()
+            .get_component::<Energy>()
+            .unwrap()
+            .can_act()
+    };
+
+    let player_moves = 3;
+    let mut monster_moves = 0;
+    for _ in 0..player_moves {
+        assert_eq!(
+            *resources.get::<TurnState>().unwrap(),
+            TurnState::AwaitingInput
+        );
+        resources.insert(TurnState::PlayerTurn);
+        loop {
+            let state = *resources.get::<TurnState>().unwrap();
+            match state {
+                TurnState::AwaitingInput => break,
+                TurnState::MonsterTurn if monster_can_act(&ecs) => monster_moves += 1,
+                _ => (),
+            }
+            schedule.execute(&mut ecs, &mut resources);
+        }
+    }
+    assert_eq!(monster_moves, player_moves * 2);
+}
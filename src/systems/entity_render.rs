@@ -5,6 +5,7 @@ use crate::prelude::*;
 #[read_component(Render)]
 #[read_component(FieldOfView)]
 #[read_component(Player)]
+#[read_component(Hidden)]
 pub fn entity_render(ecs: &SubWorld, #[resource] camera: &Camera) {
     let mut draw_batch = DrawBatch::new();
     let mut fov = <&FieldOfView>::query().filter(component::<Player>());
@@ -12,6 +13,7 @@ pub fn entity_render(ecs: &SubWorld, #[resource] camera: &Camera) {
     let offset = Point::new(camera.left_x, camera.top_y);
     let player_fov = fov.iter(ecs).nth(0).unwrap();
     <(&Point, &Render)>::query()
+        .filter(!component::<Hidden>())
         .iter(ecs)
         .filter(|(pos, _)| player_fov.is_visible(&pos))
         .for_each(|(pos, render)| {
@@ -0,0 +1,54 @@
+use crate::prelude::*;
+
+const FIRE_DAMAGE: i32 = 2;
+const GAS_DAMAGE: i32 = 1;
+const LAVA_DAMAGE: i32 = 3;
+
+#[system]
+#[read_component(Point)]
+#[read_component(Player)]
+#[read_component(DropsGold)]
+#[read_component(Name)]
+#[read_component(Enemy)]
+#[read_component(Energy)]
+#[write_component(Health)]
+pub fn environment(
+    ecs: &mut SubWorld,
+    commands: &mut CommandBuffer,
+    #[resource] map: &Map,
+    #[resource] environment: &mut Environment,
+    #[resource] rng: &mut RandomNumberGenerator,
+) {
+    // Only the last monster pass of a round, when end_turn is about to recharge energy
+    if <&Energy>::query().iter(ecs).any(Energy::can_act) {
+        return;
+    }
+    environment.step(map, rng);
+
+    let victims: Vec<(Entity, i32)> = <(Entity, &Point)>::query()
+        .filter(component::<Health>())
+        .iter(ecs)
+        .filter_map(|(entity, pos)| {
+            let idx = map.point2d_to_index(*pos);
+            let mut damage = 0;
+            if environment.fire[idx] > 0 {
+                damage += FIRE_DAMAGE;
+            }
+            if environment.gas[idx] >= HARMFUL_GAS {
+                damage += GAS_DAMAGE;
+            }
+            if map.tiles[idx] == TileType::Lava {
+                damage += LAVA_DAMAGE;
+            }
+            if damage > 0 {
+                Some((*entity, damage))
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    for (victim, damage) in victims {
+        super::combat::damage(ecs, commands, victim, damage, None);
+    }
+}
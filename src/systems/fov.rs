@@ -2,13 +2,19 @@ use crate::prelude::*;
 
 #[system]
 #[read_component(Point)]
+#[read_component(Player)]
 #[write_component(FieldOfView)]
-pub fn fov(ecs: &mut SubWorld, #[resource] map: &Map) {
-    let mut views = <(&Point, &mut FieldOfView)>::query();
+pub fn fov(ecs: &mut SubWorld, #[resource] map: &Map, #[resource] light_map: &LightMap) {
+    let mut views = <(&Point, &mut FieldOfView, Option<&Player>)>::query();
     views
         .iter_mut(ecs)
-        .filter(|(_, fov)| fov.visible_tiles == None)
-        .for_each(|(pos, mut fov)| {
-            fov.visible_tiles = Some(field_of_view_set(*pos, fov.radius, map));
+        .filter(|(_, fov, _)| fov.visible_tiles == None)
+        .for_each(|(pos, mut fov, player)| {
+            let mut visible_tiles = field_of_view_set(*pos, fov.radius, map);
+            if player.is_some() {
+                visible_tiles
+                    .retain(|pt| map.try_idx(*pt).map_or(false, |idx| light_map.is_lit(idx)));
+            }
+            fov.visible_tiles = Some(visible_tiles);
         })
 }
@@ -0,0 +1,35 @@
+use crate::prelude::*;
+
+const LIST_TOP: i32 = 4;
+const ACTION_COLUMN: i32 = 4;
+const KEY_COLUMN: i32 = 24;
+
+#[system]
+pub fn help(
+    #[resource] key: &Option<VirtualKeyCode>,
+    #[resource] turn_state: &mut TurnState,
+    #[resource] bindings: &KeyBindings,
+) {
+    if key.is_some() {
+        *turn_state = TurnState::AwaitingInput;
+    }
+
+    let mut draw_batch = DrawBatch::new();
+    draw_batch.target(HUD_LAYER.id);
+    draw_batch.print_color_centered(1, "Keys", ColorPair::new(YELLOW, BLACK));
+    Action::ALL.iter().enumerate().for_each(|(n, action)| {
+        let y = LIST_TOP + n as i32;
+        draw_batch.print(Point::new(ACTION_COLUMN, y), action.description());
+        draw_batch.print_color(
+            Point::new(KEY_COLUMN, y),
+            bindings.describe(*action),
+            ColorPair::new(CYAN, BLACK),
+        );
+    });
+    draw_batch.print_color(
+        Point::new(ACTION_COLUMN, LIST_TOP + Action::ALL.len() as i32 + 2),
+        "Edit resources/keybindings.ron to change these.  Press any key to close.",
+        ColorPair::new(GREEN, BLACK),
+    );
+    draw_batch.submit(HUD_LAYER.z_order).expect("Batch error");
+}
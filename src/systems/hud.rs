@@ -6,14 +6,40 @@ use crate::prelude::*;
 #[read_component(Item)]
 #[read_component(Carried)]
 #[read_component(Name)]
-pub fn hud(ecs: &SubWorld, #[resource] map: &Map) {
+#[read_component(Equipped)]
+#[read_component(Quantity)]
+#[read_component(Charges)]
+#[read_component(Companion)]
+#[read_component(Point)]
+#[read_component(FieldOfView)]
+#[read_component(Faction)]
+#[read_component(Purse)]
+#[read_component(Hunger)]
+#[read_component(Lantern)]
+pub fn hud(
+    ecs: &SubWorld,
+    #[resource] camera: &Camera,
+    #[resource] identification: &Identification,
+    #[resource] bindings: &KeyBindings,
+    #[resource] log: &MessageLog,
+) {
     let mut health_query = <&Health>::query().filter(component::<Player>());
     let player_health = health_query.iter(ecs).nth(0).unwrap();
 
     let mut draw_batch = DrawBatch::new();
     draw_batch.target(HUD_LAYER.id);
-    draw_batch.print_centered(0, "Explore the Dungeon.  Cursor keys to move.");
-    let health_x = (map.height - 1) * 2;
+    draw_batch.print_centered(
+        0,
+        format!(
+            "Explore the Dungeon.  {} for help.",
+            bindings.describe(Action::Help)
+        ),
+    );
+    if let Some(message) = log.latest() {
+        draw_batch.print_color_centered(1, message, ColorPair::new(CYAN, BLACK));
+    }
+    let hud_width = camera.width * (GAME_TILE_WIDTH / HUD_TILE_WIDTH);
+    let health_x = camera.height * (GAME_TILE_HEIGHT / HUD_TILE_HEIGHT) - 2;
     let health_color = match player_health.current {
         10 => WHITE,
         h if h > 7 => GREEN,
@@ -22,7 +48,7 @@ pub fn hud(ecs: &SubWorld, #[resource] map: &Map) {
     };
     draw_batch.bar_horizontal(
         Point::new(0, health_x),
-        map.width * 2,
+        hud_width,
         player_health.current,
         player_health.max,
         ColorPair::new(health_color, BLACK),
@@ -42,27 +68,102 @@ pub fn hud(ecs: &SubWorld, #[resource] map: &Map) {
         .unwrap();
 
     draw_batch.print_color_right(
-        Point::new(map.width * 2, 1),
+        Point::new(hud_width, 1),
         format!("Dungeon Level: {}", map_level + 1),
         ColorPair::new(YELLOW, BLACK),
     );
 
+    if let Some(purse) = <&Purse>::query()
+        .filter(component::<Player>())
+        .iter(ecs)
+        .nth(0)
+    {
+        draw_batch.print_color_right(
+            Point::new(hud_width, 3),
+            format!("Gold: {}", purse.0),
+            ColorPair::new(GOLD, BLACK),
+        );
+    }
+
+    if let Some(hunger) = <&Hunger>::query()
+        .filter(component::<Player>())
+        .iter(ecs)
+        .nth(0)
+    {
+        let status = match hunger.state() {
+            HungerState::Satiated => None,
+            HungerState::Hungry => Some(("Hungry", YELLOW)),
+            HungerState::Weak => Some(("Weak", ORANGE)),
+            HungerState::Fainting => Some(("Fainting", RED)),
+        };
+        if let Some((status, color)) = status {
+            draw_batch.print_color_right(
+                Point::new(hud_width, 4),
+                status,
+                ColorPair::new(color, BLACK),
+            );
+        }
+    }
+
+    if let Some(lantern) = <&Lantern>::query()
+        .filter(component::<Player>())
+        .iter(ecs)
+        .nth(0)
+    {
+        let color = match lantern.fuel {
+            0 => RED,
+            f if f < LOW_LANTERN_FUEL => YELLOW,
+            _ => WHITE,
+        };
+        draw_batch.print_color_right(
+            Point::new(hud_width, 5),
+            format!("Lantern: {}", lantern.fuel),
+            ColorPair::new(color, BLACK),
+        );
+    }
+
+    let player_fov = <&FieldOfView>::query()
+        .filter(component::<Player>())
+        .iter(ecs)
+        .nth(0)
+        .unwrap();
+    <(&Name, &Health, &Point)>::query()
+        .filter(component::<Companion>())
+        .iter(ecs)
+        .for_each(|(name, health, pos)| {
+            draw_batch.print_color_right(
+                Point::new(hud_width, 2),
+                format!(
+                    "{}: {} / {} ({})",
+                    &name.0,
+                    health.current,
+                    health.max,
+                    companion_status(ecs, *pos, player_fov)
+                ),
+                ColorPair::new(CYAN, BLACK),
+            );
+        });
+
     let player = <(Entity, &Player)>::query()
         .iter(ecs)
         .find_map(|(entity, _player)| Some(*entity))
         .unwrap();
-    let mut item_query = <(&Item, &Name, &Carried)>::query();
+    let mut item_query = <(Entity, &Item, &Carried)>::query();
     let item_top_line = 2;
     let item_column = 3;
     let mut y = item_top_line;
     item_query
         .iter(ecs)
-        .filter(|(_item, _name, carried)| carried.by == player)
-        .for_each(|(_item, name, _carried)| {
+        .filter(|(_entity, _item, carried)| carried.by == player)
+        .for_each(|(entity, _item, _carried)| {
             y += 1;
             draw_batch.print(
                 Point::new(item_column, y),
-                format!("{} : {}", y - 2, &name.0),
+                format!(
+                    "{} : {}",
+                    y - 2,
+                    item_label(&ecs.entry_ref(*entity).unwrap(), identification)
+                ),
             );
         });
     if y > item_top_line {
@@ -74,3 +175,20 @@ pub fn hud(ecs: &SubWorld, #[resource] map: &Map) {
     }
     draw_batch.submit(HUD_LAYER.z_order).expect("Batch error");
 }
+
+fn companion_status(ecs: &SubWorld, pos: Point, player_fov: &FieldOfView) -> &'static str {
+    let fighting = <(&Point, &Faction)>::query()
+        .filter(component::<Health>())
+        .iter(ecs)
+        .any(|(other, faction)| {
+            faction.is_hostile_to(Faction::Player)
+                && DistanceAlg::Pythagoras.distance2d(pos, *other) < 1.5
+        });
+    if fighting {
+        "fighting"
+    } else if player_fov.is_visible(&pos) {
+        "following"
+    } else {
+        "out of sight"
+    }
+}
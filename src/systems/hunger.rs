@@ -0,0 +1,17 @@
+use crate::prelude::*;
+
+const STARVATION_INTERVAL: i32 = 10;
+
+#[system]
+#[write_component(Hunger)]
+#[write_component(Health)]
+pub fn hunger(ecs: &mut SubWorld) {
+    <(&mut Hunger, &mut Health)>::query()
+        .iter_mut(ecs)
+        .for_each(|(hunger, health)| {
+            hunger.satiety -= 1;
+            if hunger.satiety <= 0 && hunger.satiety % STARVATION_INTERVAL == 0 {
+                health.current -= 1;
+            }
+        });
+}
@@ -0,0 +1,245 @@
+use crate::prelude::*;
+use legion::world::EntryRef;
+
+const PAGE_SIZE: usize = 10;
+const LIST_TOP: i32 = 4;
+const LIST_COLUMN: i32 = 4;
+
+#[system]
+#[read_component(Player)]
+#[read_component(Item)]
+#[read_component(Carried)]
+#[read_component(Name)]
+#[read_component(Point)]
+#[read_component(Equipped)]
+#[read_component(Quantity)]
+#[read_component(Charges)]
+#[read_component(Weapon)]
+#[read_component(Damage)]
+#[read_component(ProvidesHealing)]
+#[read_component(ProvidesDungeonMap)]
+#[read_component(ProvidesCharm)]
+#[read_component(ProvidesIdentify)]
+#[read_component(ProvidesNutrition)]
+#[read_component(ProvidesDigging)]
+#[read_component(ProvidesBlast)]
+#[read_component(ProvidesFire)]
+#[read_component(ProvidesGas)]
+#[read_component(ProvidesFuel)]
+#[read_component(LightSource)]
+#[read_component(FieldOfView)]
+#[read_component(Faction)]
+#[read_component(Health)]
+pub fn inventory(
+    ecs: &SubWorld,
+    commands: &mut CommandBuffer,
+    #[resource] key: &Option<VirtualKeyCode>,
+    #[resource] turn_state: &mut TurnState,
+    #[resource] screen: &mut InventoryScreen,
+    #[resource] bindings: &KeyBindings,
+    #[resource] identification: &Identification,
+    #[resource] map: &Map,
+) {
+    let (player, player_pos) = <(Entity, &Point)>::query()
+        .filter(component::<Player>())
+        .iter(ecs)
+        .find_map(|(entity, pos)| Some((*entity, *pos)))
+        .unwrap();
+    let items: Vec<Entity> = <(Entity, &Item, &Carried)>::query()
+        .iter(ecs)
+        .filter(|(_, _, carried)| carried.by == player)
+        .map(|(entity, _, _)| *entity)
+        .collect();
+    let last_item = items.len().saturating_sub(1);
+    screen.selected = screen.selected.min(last_item);
+
+    if let Some(key) = key {
+        let selected_item = items.get(screen.selected).copied();
+        match (key, bindings.action(*key)) {
+            (VirtualKeyCode::Escape, _) | (_, Some(Action::Inventory)) => {
+                *turn_state = TurnState::AwaitingInput
+            }
+            (VirtualKeyCode::PageUp, _) => {
+                screen.selected = screen.selected.saturating_sub(PAGE_SIZE)
+            }
+            (VirtualKeyCode::PageDown, _) => screen.selected += PAGE_SIZE,
+            (VirtualKeyCode::U | VirtualKeyCode::Return, _) => {
+                if let Some(item) = selected_item {
+                    commands.push((
+                        (),
+                        ActivateItem {
+                            used_by: player,
+                            item,
+                        },
+                    ));
+                    *turn_state = TurnState::PlayerTurn;
+                }
+            }
+            (VirtualKeyCode::E, _) => {
+                if let Some(item) = selected_item {
+                    if equip(ecs, commands, player, item) {
+                        *turn_state = TurnState::PlayerTurn;
+                    }
+                }
+            }
+            (VirtualKeyCode::D, _) => {
+                if let (Some(item), false) = (selected_item, map.swallows_items(player_pos)) {
+                    commands.remove_component::<Carried>(item);
+                    commands.remove_component::<Equipped>(item);
+                    commands.add_component(item, player_pos);
+                    *turn_state = TurnState::PlayerTurn;
+                }
+            }
+            (VirtualKeyCode::T, _) => {
+                if let Some(item) = selected_item {
+                    if let Some(target) = throw_target(ecs, player_pos) {
+                        commands.push((
+                            (),
+                            WantsToThrow {
+                                thrower: player,
+                                item,
+                                target,
+                            },
+                        ));
+                        *turn_state = TurnState::PlayerTurn;
+                    }
+                }
+            }
+            (_, Some(Action::MoveUp)) => screen.selected = screen.selected.saturating_sub(1),
+            (_, Some(Action::MoveDown)) => screen.selected += 1,
+            _ => (),
+        }
+        screen.selected = screen.selected.min(last_item);
+    }
+
+    let mut draw_batch = DrawBatch::new();
+    draw_batch.target(HUD_LAYER.id);
+    draw_batch.print_color_centered(1, "Inventory", ColorPair::new(YELLOW, BLACK));
+    if items.is_empty() {
+        draw_batch.print_centered(LIST_TOP, "You aren't carrying anything.");
+    }
+    let page = screen.selected / PAGE_SIZE;
+    let pages = (items.len() + PAGE_SIZE - 1) / PAGE_SIZE;
+    items
+        .iter()
+        .enumerate()
+        .skip(page * PAGE_SIZE)
+        .take(PAGE_SIZE)
+        .for_each(|(n, item)| {
+            let entry = ecs.entry_ref(*item).unwrap();
+            let (marker, color) = if n == screen.selected {
+                (">", YELLOW)
+            } else {
+                (" ", WHITE)
+            };
+            draw_batch.print_color(
+                Point::new(LIST_COLUMN, LIST_TOP + (n - page * PAGE_SIZE) as i32),
+                format!("{} {}", marker, item_label(&entry, identification)),
+                ColorPair::new(color, BLACK),
+            );
+        });
+    let footer = LIST_TOP + PAGE_SIZE as i32 + 1;
+    if pages > 1 {
+        draw_batch.print_color(
+            Point::new(LIST_COLUMN, footer),
+            format!("Page {} / {}  (PgUp / PgDn)", page + 1, pages),
+            ColorPair::new(GRAY, BLACK),
+        );
+    }
+    if let Some(item) = items.get(screen.selected) {
+        draw_batch.print(
+            Point::new(LIST_COLUMN, footer + 2),
+            describe_item(&ecs.entry_ref(*item).unwrap(), identification),
+        );
+    }
+    draw_batch.print_color(
+        Point::new(LIST_COLUMN, footer + 4),
+        format!(
+            "[U]se  [E]quip  [D]rop  [T]hrow  [{} / Esc] Close",
+            bindings.describe(Action::Inventory)
+        ),
+        ColorPair::new(GREEN, BLACK),
+    );
+    draw_batch.submit(HUD_LAYER.z_order).expect("Batch error");
+}
+
+fn equip(ecs: &SubWorld, commands: &mut CommandBuffer, player: Entity, item: Entity) -> bool {
+    let is_weapon = ecs
+        .entry_ref(item)
+        .map_or(false, |entry| entry.get_component::<Weapon>().is_ok());
+    if is_weapon {
+        <(Entity, &Carried)>::query()
+            .filter(component::<Weapon>() & component::<Equipped>())
+            .iter(ecs)
+            .filter(|(_, carried)| carried.by == player)
+            .for_each(|(weapon, _)| commands.remove_component::<Equipped>(*weapon));
+        commands.add_component(item, Equipped);
+    }
+    is_weapon
+}
+
+fn throw_target(ecs: &SubWorld, player_pos: Point) -> Option<Point> {
+    let player_fov = <&FieldOfView>::query()
+        .filter(component::<Player>())
+        .iter(ecs)
+        .nth(0)?;
+    <(&Point, &Faction)>::query()
+        .filter(component::<Health>())
+        .iter(ecs)
+        .filter(|(pos, faction)| {
+            player_fov.is_visible(pos) && faction.is_hostile_to(Faction::Player)
+        })
+        .map(|(pos, _)| *pos)
+        .min_by(|a, b| {
+            let distance_a = DistanceAlg::Pythagoras.distance2d(player_pos, *a);
+            let distance_b = DistanceAlg::Pythagoras.distance2d(player_pos, *b);
+            distance_a.partial_cmp(&distance_b).unwrap()
+        })
+}
+
+pub fn describe_item(item: &EntryRef, identification: &Identification) -> String {
+    let known = item
+        .get_component::<Name>()
+        .map_or(true, |name| identification.is_known(&name.0));
+    if !known {
+        String::from("You won't know what this does until you try it.")
+    } else if let Ok(healing) = item.get_component::<ProvidesHealing>() {
+        format!("Restores {} health.", healing.amount)
+    } else if item.get_component::<ProvidesDungeonMap>().is_ok() {
+        String::from("Reveals the layout of this dungeon level.")
+    } else if item.get_component::<ProvidesCharm>().is_ok() {
+        String::from("Turns the nearest creature you can see into an ally.")
+    } else if item.get_component::<ProvidesIdentify>().is_ok() {
+        String::from("Identifies everything you are carrying.")
+    } else if item.get_component::<ProvidesDigging>().is_ok() {
+        String::from("Lets you dig through walls by walking into them.")
+    } else if let Ok(blast) = item.get_component::<ProvidesBlast>() {
+        format!(
+            "Blasts apart walls and creatures within {} tiles.",
+            blast.radius
+        )
+    } else if let Ok(fire) = item.get_component::<ProvidesFire>() {
+        format!(
+            "Bursts into flames within {} tiles of where it lands.",
+            fire.radius
+        )
+    } else if let Ok(gas) = item.get_component::<ProvidesGas>() {
+        format!(
+            "Releases a cloud of poison gas within {} tiles of where it lands.",
+            gas.radius
+        )
+    } else if let Ok(fuel) = item.get_component::<ProvidesFuel>() {
+        format!("Refills your lantern with {} turns of oil.", fuel.amount)
+    } else if let Ok(light) = item.get_component::<LightSource>() {
+        format!("Glows softly, lighting {} tiles around you.", light.radius)
+    } else if let Ok(nutrition) = item.get_component::<ProvidesNutrition>() {
+        format!(
+            "Food that staves off hunger for {} turns.",
+            nutrition.amount
+        )
+    } else if let Ok(damage) = item.get_component::<Damage>() {
+        format!("A weapon that adds {} damage to your attacks.", damage.0)
+    } else {
+        String::from("You aren't sure what this does.")
+    }
+}
@@ -0,0 +1,7 @@
+use crate::prelude::*;
+
+#[system(for_each)]
+pub fn lantern(lantern: &mut Lantern, light: &mut LightSource) {
+    lantern.fuel = i32::max(0, lantern.fuel - 1);
+    *light = lantern.light();
+}
@@ -0,0 +1,50 @@
+use crate::prelude::*;
+
+#[system]
+#[read_component(Point)]
+#[read_component(LightSource)]
+#[read_component(Carried)]
+#[read_component(Player)]
+#[write_component(FieldOfView)]
+pub fn lighting(
+    #[state] cache: &mut LitSources,
+    ecs: &mut SubWorld,
+    #[resource] map: &Map,
+    #[resource] light_map: &mut LightMap,
+) {
+    let sources: Vec<(Point, i32)> = <(Entity, &LightSource)>::query()
+        .iter(ecs)
+        .filter_map(|(entity, light)| {
+            let entry = ecs.entry_ref(*entity).ok()?;
+            let pos = match entry.get_component::<Carried>() {
+                Ok(carried) => *ecs
+                    .entry_ref(carried.by)
+                    .ok()?
+                    .get_component::<Point>()
+                    .ok()?,
+                Err(_) => *entry.get_component::<Point>().ok()?,
+            };
+            Some((pos, light.radius))
+        })
+        .collect();
+    let key = Some((map.revision, sources));
+    if cache.key == key {
+        return;
+    }
+    cache.key = key;
+    let sources = &cache.key.as_ref().unwrap().1;
+
+    let lights = LightMap::compute(map, sources);
+    if lights != *light_map {
+        *light_map = lights;
+        <&mut FieldOfView>::query()
+            .filter(component::<Player>())
+            .iter_mut(ecs)
+            .for_each(|fov| fov.visible_tiles = None);
+    }
+}
+
+#[derive(Default)]
+pub struct LitSources {
+    key: Option<(u64, Vec<(Point, i32)>)>,
+}
@@ -0,0 +1,299 @@
+use crate::prelude::*;
+use legion::world::EntryRef;
+
+const PANEL_WIDTH: i32 = 46;
+const PANEL_TOP: i32 = 3;
+
+#[system]
+#[read_component(Point)]
+#[read_component(Player)]
+#[read_component(Name)]
+#[read_component(Description)]
+#[read_component(Hidden)]
+#[read_component(Health)]
+#[read_component(Damage)]
+#[read_component(Faction)]
+#[read_component(FieldOfView)]
+#[read_component(ChasingPlayer)]
+#[read_component(MovingRandomly)]
+#[read_component(FollowingPlayer)]
+#[read_component(Merchant)]
+#[read_component(Hunger)]
+#[read_component(Item)]
+#[read_component(Weapon)]
+#[read_component(Equipped)]
+#[read_component(Carried)]
+#[read_component(Quantity)]
+#[read_component(Charges)]
+#[read_component(Value)]
+#[read_component(ProvidesHealing)]
+#[read_component(ProvidesDungeonMap)]
+#[read_component(ProvidesCharm)]
+#[read_component(ProvidesIdentify)]
+#[read_component(ProvidesNutrition)]
+#[read_component(ProvidesDigging)]
+#[read_component(ProvidesBlast)]
+#[read_component(ProvidesFire)]
+#[read_component(ProvidesGas)]
+#[read_component(ProvidesFuel)]
+#[read_component(LightSource)]
+pub fn look(
+    ecs: &SubWorld,
+    #[resource] key: &Option<VirtualKeyCode>,
+    #[resource] turn_state: &mut TurnState,
+    #[resource] screen: &mut LookScreen,
+    #[resource] bindings: &KeyBindings,
+    #[resource] map: &Map,
+    #[resource] camera: &Camera,
+    #[resource] identification: &Identification,
+    #[resource] environment: &Environment,
+) {
+    let (player, player_pos, player_fov) = <(Entity, &Point, &FieldOfView)>::query()
+        .filter(component::<Player>())
+        .iter(ecs)
+        .find_map(|(entity, pos, fov)| Some((*entity, *pos, fov)))
+        .unwrap();
+    let mut cursor = screen.cursor.unwrap_or(player_pos);
+    if let Some(key) = key {
+        match (key, bindings.action(*key)) {
+            (VirtualKeyCode::Escape, _) | (_, Some(Action::Look)) => {
+                screen.cursor = None;
+                *turn_state = TurnState::AwaitingInput;
+                return;
+            }
+            (_, Some(action)) => {
+                if let Some(step) = action.step() {
+                    let next = cursor + step;
+                    if (camera.left_x..=camera.right_x).contains(&next.x)
+                        && (camera.top_y..=camera.bottom_y).contains(&next.y)
+                        && map.in_bounds(next)
+                    {
+                        cursor = next;
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+    screen.cursor = Some(cursor);
+
+    let mut cursor_batch = DrawBatch::new();
+    cursor_batch.target(BACKGROUND_LAYER.id);
+    cursor_batch.set_bg(
+        cursor - Point::new(camera.left_x, camera.top_y),
+        RGB::named(DARK_CYAN),
+    );
+    cursor_batch
+        .submit(BACKGROUND_LAYER.z_order + 1)
+        .expect("Batch error");
+
+    let visible = player_fov.is_visible(&cursor);
+    let mut lines: Vec<(String, (u8, u8, u8))> = Vec::new();
+    match map.try_idx(cursor) {
+        Some(idx) if visible || map.revealed_tiles[idx] => {
+            let memory = if visible { "" } else { " (remembered)" };
+            lines.push((
+                format!("{}{}", terrain_name(map.tiles[idx]), memory),
+                YELLOW,
+            ));
+            if visible && environment.fire[idx] > 0 {
+                lines.push((String::from("The ground here is burning."), ORANGE));
+            }
+            if visible && environment.gas[idx] >= HARMFUL_GAS {
+                lines.push((String::from("Poison gas hangs in the air."), GREEN));
+            }
+        }
+        _ => lines.push((String::from("You haven't seen this place."), GRAY)),
+    }
+    if visible {
+        let equipped_damage = <(&Carried, &Damage)>::query()
+            .filter(component::<Weapon>() & component::<Equipped>())
+            .iter(ecs)
+            .find(|(carried, _)| carried.by == player)
+            .map(|(_, damage)| damage.0);
+        <(Entity, &Point)>::query()
+            .filter(component::<Name>() & !component::<Hidden>())
+            .iter(ecs)
+            .filter(|(_, pos)| **pos == cursor)
+            .for_each(|(entity, _)| {
+                let entry = ecs.entry_ref(*entity).unwrap();
+                lines.push((String::new(), WHITE));
+                describe_entity(&entry, identification, equipped_damage, environment, map)
+                    .into_iter()
+                    .for_each(|line| lines.push(line));
+            });
+    }
+
+    let mut draw_batch = DrawBatch::new();
+    draw_batch.target(HUD_LAYER.id);
+    draw_batch.print_color_centered(
+        1,
+        format!(
+            "Look around with the movement keys.  {} or Escape to stop.",
+            bindings.describe(Action::Look)
+        ),
+        ColorPair::new(YELLOW, BLACK),
+    );
+    let scale = GAME_TILE_WIDTH / HUD_TILE_WIDTH;
+    let hud_width = camera.width * scale;
+    let panel_x = if (cursor.x - camera.left_x) * scale < hud_width / 2 {
+        hud_width - PANEL_WIDTH - 1
+    } else {
+        1
+    };
+    let wrapped: Vec<(String, (u8, u8, u8))> = lines
+        .iter()
+        .flat_map(|(line, color)| {
+            wrap(line, PANEL_WIDTH as usize - 3)
+                .into_iter()
+                .map(move |part| (part, *color))
+        })
+        .collect();
+    draw_batch.draw_box(
+        Rect::with_size(panel_x, PANEL_TOP, PANEL_WIDTH, wrapped.len() as i32 + 1),
+        ColorPair::new(WHITE, BLACK),
+    );
+    wrapped.iter().enumerate().for_each(|(n, (line, color))| {
+        draw_batch.print_color(
+            Point::new(panel_x + 2, PANEL_TOP + 1 + n as i32),
+            line,
+            ColorPair::new(*color, BLACK),
+        );
+    });
+    draw_batch.submit(HUD_LAYER.z_order).expect("Batch error");
+}
+
+fn describe_entity(
+    entry: &EntryRef,
+    identification: &Identification,
+    equipped_damage: Option<i32>,
+    environment: &Environment,
+    map: &Map,
+) -> Vec<(String, (u8, u8, u8))> {
+    let mut lines = Vec::new();
+    let is_player = entry.get_component::<Player>().is_ok();
+    let name = entry
+        .get_component::<Name>()
+        .map_or("???", |name| identification.display_name(&name.0));
+    let name = if is_player { "You" } else { name };
+    lines.push((String::from(name), CYAN));
+    let known = entry
+        .get_component::<Name>()
+        .map_or(true, |name| identification.is_known(&name.0));
+    if let (Ok(description), true) = (entry.get_component::<Description>(), known) {
+        lines.push((description.0.clone(), WHITE));
+    }
+
+    if let Ok(health) = entry.get_component::<Health>() {
+        lines.push((
+            format!("Health: {} / {}", health.current, health.max),
+            WHITE,
+        ));
+        if let Ok(damage) = entry.get_component::<Damage>() {
+            lines.push((format!("Damage: {}", damage.0), WHITE));
+        }
+        if let Some(state) = ai_state(entry) {
+            lines.push((String::from(state), WHITE));
+        }
+        let mut statuses = Vec::new();
+        if let Ok(hunger) = entry.get_component::<Hunger>() {
+            match hunger.state() {
+                HungerState::Satiated => (),
+                HungerState::Hungry => statuses.push("hungry"),
+                HungerState::Weak => statuses.push("weak"),
+                HungerState::Fainting => statuses.push("fainting"),
+            }
+        }
+        if let Some(idx) = entry
+            .get_component::<Point>()
+            .ok()
+            .and_then(|pos| map.try_idx(*pos))
+        {
+            if environment.fire[idx] > 0 || map.tiles[idx] == TileType::Lava {
+                statuses.push("burning");
+            }
+            if environment.gas[idx] >= HARMFUL_GAS {
+                statuses.push("choking");
+            }
+        }
+        if !statuses.is_empty() {
+            lines.push((format!("Status: {}", statuses.join(", ")), ORANGE));
+        }
+    }
+
+    if entry.get_component::<Item>().is_ok() {
+        lines.push((item_label(entry, identification), WHITE));
+        lines.push((
+            super::inventory::describe_item(entry, identification),
+            WHITE,
+        ));
+        if let (Ok(damage), true) = (
+            entry.get_component::<Damage>(),
+            entry.get_component::<Weapon>().is_ok(),
+        ) {
+            let comparison = match equipped_damage {
+                Some(equipped) => format!("{:+} compared with your weapon", damage.0 - equipped),
+                None => String::from("you have no weapon equipped"),
+            };
+            lines.push((format!("Damage: {} ({})", damage.0, comparison), GREEN));
+        }
+        if let Ok(value) = entry.get_component::<Value>() {
+            lines.push((format!("Worth about {} gold", value.0), GOLD));
+        }
+    }
+    lines
+}
+
+fn ai_state(entry: &EntryRef) -> Option<&'static str> {
+    if entry.get_component::<Player>().is_ok() {
+        None
+    } else if entry.get_component::<Merchant>().is_ok() {
+        Some("Waiting for customers")
+    } else if entry.get_component::<FollowingPlayer>().is_ok() {
+        Some("Following you")
+    } else if entry.get_component::<ChasingPlayer>().is_ok() {
+        let hostile = entry
+            .get_component::<Faction>()
+            .map_or(false, |faction| faction.is_hostile_to(Faction::Player));
+        Some(if hostile {
+            "Hunting you"
+        } else {
+            "Minding its own business"
+        })
+    } else if entry.get_component::<MovingRandomly>().is_ok() {
+        Some("Wandering")
+    } else {
+        None
+    }
+}
+
+fn terrain_name(tile: TileType) -> &'static str {
+    match tile {
+        TileType::Wall | TileType::SecretDoor => "A wall",
+        TileType::Floor => "Open floor",
+        TileType::Exit => "Stairs leading down",
+        TileType::Door => "A closed door",
+        TileType::OpenDoor => "An open door",
+        TileType::LockedDoor(_) => "A locked door",
+        TileType::DeepWater => "Deep water",
+        TileType::Lava => "Molten lava",
+        TileType::Chasm => "A bottomless chasm",
+        TileType::Bridge => "A rickety bridge",
+    }
+}
+
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = vec![String::new()];
+    text.split_whitespace().for_each(|word| {
+        let line = lines.last_mut().unwrap();
+        if !line.is_empty() && line.len() + word.len() + 1 > width {
+            lines.push(String::from(word));
+        } else {
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(word);
+        }
+    });
+    lines
+}
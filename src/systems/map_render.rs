@@ -8,33 +8,62 @@ pub fn map_render(
     #[resource] map: &Map,
     #[resource] camera: &Camera,
     #[resource] theme: &Option<Box<dyn MapTheme>>,
+    #[resource] environment: &Environment,
+    #[resource] light_map: &LightMap,
 ) {
     let mut fov = <&FieldOfView>::query().filter(component::<Player>());
     let mut draw_batch = DrawBatch::new();
     draw_batch.target(BACKGROUND_LAYER.id);
     let player_fov = fov.iter(ecs).nth(0).unwrap();
-    for (y, x) in (camera.top_y..=camera.bottom_y).cartesian_product(camera.left_x..=camera.right_x)
-    {
+    let rows = camera.top_y.max(0)..=camera.bottom_y.min(map.height - 1);
+    let columns = camera.left_x.max(0)..=camera.right_x.min(map.width - 1);
+    for (y, x) in rows.cartesian_product(columns) {
         let pt = Point::new(x, y);
         let offset = Point::new(camera.left_x, camera.top_y);
         let visible_to_player = player_fov.is_visible(&pt);
         let idx = map.index_for(x, y);
         if map.in_bounds(pt) && (visible_to_player | map.revealed_tiles[idx]) {
             let tint = if player_fov.is_visible(&pt) {
-                WHITE
+                let lit_color = match map.tiles[idx] {
+                    TileType::DeepWater => BLUE,
+                    TileType::Lava => ORANGE,
+                    TileType::Bridge => BROWN1,
+                    _ => WHITE,
+                };
+                RGB::named(lit_color).lerp(RGB::named(DARK_GRAY), 1.0 - light_map.level(idx))
             } else {
-                DARK_GRAY
+                RGB::named(DARK_GRAY)
+            };
+            let background = if !visible_to_player {
+                BLACK
+            } else if environment.fire[idx] > 0 {
+                if environment.fire[idx] >= FIRE_DURATION {
+                    ORANGE_RED
+                } else {
+                    DARK_RED
+                }
+            } else if environment.gas[idx] >= HARMFUL_GAS {
+                DARKOLIVEGREEN
+            } else {
+                BLACK
             };
             let glyph = if let Some(theme) = theme {
                 theme.tile_to_render(map.tiles[idx])
             } else {
                 match map.tiles[idx] {
                     TileType::Floor => *DUNGEON_FLOOR_GLYPH,
-                    TileType::Wall => *DUNGEON_WALL_GLYPH,
+                    TileType::Wall | TileType::SecretDoor => *DUNGEON_WALL_GLYPH,
                     TileType::Exit => *STAIRS_GLYPH,
+                    TileType::Door => *DOOR_GLYPH,
+                    TileType::OpenDoor => *OPEN_DOOR_GLYPH,
+                    TileType::LockedDoor(_) => *LOCKED_DOOR_GLYPH,
+                    TileType::DeepWater => *DUNGEON_WATER_GLYPH,
+                    TileType::Lava => *LAVA_GLYPH,
+                    TileType::Chasm => *CHASM_GLYPH,
+                    TileType::Bridge => *BRIDGE_GLYPH,
                 }
             };
-            draw_batch.set(pt - offset, ColorPair::new(tint, BLACK), glyph);
+            draw_batch.set(pt - offset, ColorPair::new(tint, background), glyph);
         }
     }
     draw_batch
@@ -0,0 +1,56 @@
+use crate::prelude::*;
+
+const MINIMAP_TOP: i32 = 7;
+
+#[system]
+#[read_component(Point)]
+#[read_component(Player)]
+#[read_component(FieldOfView)]
+#[read_component(Faction)]
+#[read_component(Health)]
+pub fn minimap(ecs: &SubWorld, #[resource] map: &Map, #[resource] camera: &Camera) {
+    let (player_pos, player_fov) = <(&Point, &FieldOfView)>::query()
+        .filter(component::<Player>())
+        .iter(ecs)
+        .find_map(|(pos, fov)| Some((*pos, fov)))
+        .unwrap();
+    let cells = Point::new(
+        (map.width + MINIMAP_SCALE - 1) / MINIMAP_SCALE,
+        (map.height + MINIMAP_SCALE - 1) / MINIMAP_SCALE,
+    );
+    let hud_width = camera.width * (GAME_TILE_WIDTH / HUD_TILE_WIDTH);
+    let origin = Point::new(hud_width - cells.x - 1, MINIMAP_TOP);
+
+    let mut draw_batch = DrawBatch::new();
+    draw_batch.target(HUD_LAYER.id);
+    draw_batch.draw_hollow_box(
+        Rect::with_size(origin.x - 1, origin.y - 1, cells.x + 1, cells.y + 1),
+        ColorPair::new(GRAY, BLACK),
+    );
+    for (y, x) in (0..cells.y).cartesian_product(0..cells.x) {
+        let cell = Point::new(x, y);
+        let color = revealed_in_cell(map, cell, MINIMAP_SCALE).map_or(BLACK, tile_color);
+        draw_batch.set(origin + cell, ColorPair::new(color, color), to_cp437(' '));
+    }
+    <(&Point, &Faction)>::query()
+        .filter(component::<Health>())
+        .iter(ecs)
+        .filter(|(pos, faction)| {
+            faction.is_hostile_to(Faction::Player) && player_fov.is_visible(pos)
+        })
+        .for_each(|(pos, _)| {
+            draw_batch.set(
+                origin + *pos / MINIMAP_SCALE,
+                ColorPair::new(RED, BLACK),
+                to_cp437('*'),
+            );
+        });
+    draw_batch.set(
+        origin + player_pos / MINIMAP_SCALE,
+        ColorPair::new(YELLOW, BLACK),
+        to_cp437('@'),
+    );
+    draw_batch
+        .submit(HUD_LAYER.z_order + 10)
+        .expect("Batch error");
+}
@@ -1,27 +1,48 @@
+mod character;
 mod chasing;
 mod combat;
 mod end_turn;
+mod energy;
 mod entity_render;
+mod environment;
 mod fov;
+mod help;
 mod hud;
+mod hunger;
+mod inventory;
+mod lantern;
+mod lighting;
+mod look;
 mod map_render;
+mod minimap;
 mod monster_monitor;
 mod movement;
+mod overview;
 mod player_input;
 mod random_move;
+mod regeneration;
+mod search;
+mod statistics;
+mod throwing;
 mod tooltips;
+mod trade;
+mod traps;
+mod travel;
 mod use_items;
 
 use crate::prelude::*;
 
 pub fn build_input_schedule() -> Schedule {
     Schedule::builder()
+        .add_system(travel::travel_system())
         .add_system(player_input::player_input_system())
+        .add_system(lighting::lighting_system(lighting::LitSources::default()))
         .add_system(fov::fov_system())
         .flush()
         .add_system(map_render::map_render_system())
         .add_system(entity_render::entity_render_system())
         .add_system(hud::hud_system())
+        .add_system(minimap::minimap_system())
         .add_system(tooltips::tooltips_system())
         .add_system(monster_monitor::monster_monitor_system())
         .build()
@@ -31,14 +52,26 @@ pub fn build_player_schedule() -> Schedule {
     Schedule::builder()
         .add_system(use_items::use_items_system())
         .add_system(combat::combat_system())
+        .add_system(throwing::throwing_system())
+        .add_system(search::search_system())
         .flush()
         .add_system(movement::movement_system())
         .flush()
+        .add_system(traps::traps_system())
+        .flush()
+        .add_system(energy::spend_energy_system())
+        .add_system(hunger::hunger_system())
+        .add_system(lantern::lantern_system())
+        .add_system(lighting::lighting_system(lighting::LitSources::default()))
         .add_system(fov::fov_system())
         .flush()
+        .add_system(regeneration::regeneration_system())
         .add_system(map_render::map_render_system())
         .add_system(entity_render::entity_render_system())
         .add_system(hud::hud_system())
+        .add_system(minimap::minimap_system())
+        .add_system(statistics::statistics_system())
+        .add_system(statistics::count_turns_system())
         .add_system(end_turn::end_turn_system())
         .add_system(monster_monitor::monster_monitor_system())
         .build()
@@ -47,19 +80,63 @@ pub fn build_player_schedule() -> Schedule {
 pub fn build_monster_schedule() -> Schedule {
     Schedule::builder()
         .add_system(random_move::random_move_system())
-        .add_system(chasing::chasing_system())
+        .add_system(chasing::chasing_system(chasing::PlayerDistances::default()))
         .flush()
         .add_system(use_items::use_items_system())
         .add_system(combat::combat_system())
         .flush()
         .add_system(movement::movement_system())
         .flush()
+        .add_system(traps::traps_system())
+        .flush()
+        .add_system(energy::spend_energy_system())
+        .flush()
+        .add_system(environment::environment_system())
+        .add_system(lighting::lighting_system(lighting::LitSources::default()))
         .add_system(fov::fov_system())
         .flush()
         .add_system(map_render::map_render_system())
         .add_system(entity_render::entity_render_system())
         .add_system(hud::hud_system())
+        .add_system(minimap::minimap_system())
+        .add_system(statistics::statistics_system())
         .add_system(end_turn::end_turn_system())
         .add_system(monster_monitor::monster_monitor_system())
         .build()
 }
+
+pub fn build_inventory_schedule() -> Schedule {
+    Schedule::builder()
+        .add_system(inventory::inventory_system())
+        .build()
+}
+
+pub fn build_help_schedule() -> Schedule {
+    Schedule::builder().add_system(help::help_system()).build()
+}
+
+pub fn build_look_schedule() -> Schedule {
+    Schedule::builder()
+        .add_system(look::look_system())
+        .add_system(map_render::map_render_system())
+        .add_system(entity_render::entity_render_system())
+        .build()
+}
+
+pub fn build_overview_schedule() -> Schedule {
+    Schedule::builder()
+        .add_system(overview::overview_system())
+        .build()
+}
+
+pub fn build_character_schedule() -> Schedule {
+    Schedule::builder()
+        .add_system(character::character_system())
+        .build()
+}
+
+pub fn build_trade_schedule() -> Schedule {
+    Schedule::builder()
+        .add_system(trade::trade_system())
+        .build()
+}
@@ -3,8 +3,13 @@ use crate::prelude::*;
 #[system(for_each)]
 #[read_component(Point)]
 #[read_component(Player)]
-#[read_component(Enemy)]
+#[read_component(Health)]
 #[read_component(FieldOfView)]
+#[read_component(Carried)]
+#[read_component(Key)]
+#[read_component(Trap)]
+#[read_component(Faction)]
+#[read_component(ProvidesDigging)]
 pub fn movement(
     entity: &Entity,
     want_move: &WantsToMove,
@@ -13,13 +18,54 @@ pub fn movement(
     ecs: &mut SubWorld,
     commands: &mut CommandBuffer,
 ) {
+    if let Some(idx) = map.try_idx(want_move.destination) {
+        let changed = match map.tiles[idx] {
+            TileType::Door => {
+                map.set_tile(idx, TileType::OpenDoor);
+                true
+            }
+            TileType::LockedDoor(id) if use_key(ecs, commands, want_move.entity, id) => {
+                map.set_tile(idx, TileType::OpenDoor);
+                true
+            }
+            TileType::Wall if carries_digger(ecs, want_move.entity) => {
+                map.dig(want_move.destination)
+            }
+            _ => false,
+        };
+        if changed {
+            <(Entity, &FieldOfView)>::query()
+                .iter(ecs)
+                .for_each(|(viewer, fov)| commands.add_component(*viewer, fov.clone_dirty()));
+            commands.remove(*entity);
+            return;
+        }
+    }
     if map.can_enter_tile(want_move.destination) {
-        if !<&Point>::query()
-            .filter(component::<Enemy>())
+        let occupant = <(Entity, &Point)>::query()
+            .filter(component::<Health>())
             .iter(ecs)
-            .any(|pt| *pt == want_move.destination)
-        {
+            .find(|(_, pt)| **pt == want_move.destination)
+            .map(|(occupant, _)| *occupant);
+        let free = match occupant {
+            Some(occupant) => swap_with_ally(ecs, commands, want_move.entity, occupant),
+            None => true,
+        };
+        if free {
             commands.add_component(want_move.entity, want_move.destination);
+            <(Entity, &Point)>::query()
+                .filter(component::<Trap>())
+                .iter(ecs)
+                .filter(|(_, pos)| **pos == want_move.destination)
+                .for_each(|(trap, _)| {
+                    commands.push((
+                        (),
+                        TriggerTrap {
+                            trap: *trap,
+                            victim: want_move.entity,
+                        },
+                    ));
+                });
             if let Ok(entry) = ecs.entry_ref(want_move.entity) {
                 if let Ok(fov) = entry.get_component::<FieldOfView>() {
                     commands.add_component(want_move.entity, fov.clone_dirty());
@@ -38,3 +84,46 @@ pub fn movement(
     }
     commands.remove(*entity);
 }
+
+fn use_key(ecs: &SubWorld, commands: &mut CommandBuffer, holder: Entity, id: u8) -> bool {
+    let key = <(Entity, &Key, &Carried)>::query()
+        .iter(ecs)
+        .find(|(_, key, carried)| key.0 == id && carried.by == holder)
+        .map(|(entity, _, _)| *entity);
+    if let Some(key) = key {
+        commands.remove(key);
+    }
+    key.is_some()
+}
+
+fn carries_digger(ecs: &SubWorld, holder: Entity) -> bool {
+    <&Carried>::query()
+        .filter(component::<ProvidesDigging>())
+        .iter(ecs)
+        .any(|carried| carried.by == holder)
+}
+
+fn swap_with_ally(
+    ecs: &SubWorld,
+    commands: &mut CommandBuffer,
+    mover: Entity,
+    occupant: Entity,
+) -> bool {
+    let is_player = ecs
+        .entry_ref(mover)
+        .map_or(false, |entry| entry.get_component::<Player>().is_ok());
+    if !is_player || reaction_between(ecs, mover, occupant) != Reaction::Allied {
+        return false;
+    }
+    if let Ok(pos) = ecs.entry_ref(mover).unwrap().get_component::<Point>() {
+        commands.add_component(occupant, *pos);
+    }
+    if let Ok(fov) = ecs
+        .entry_ref(occupant)
+        .unwrap()
+        .get_component::<FieldOfView>()
+    {
+        commands.add_component(occupant, fov.clone_dirty());
+    }
+    true
+}
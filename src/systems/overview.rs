@@ -0,0 +1,115 @@
+use crate::prelude::*;
+
+const MAP_TOP: i32 = 3;
+const PAN_STEP: i32 = 4;
+
+#[system]
+#[read_component(Point)]
+#[read_component(Player)]
+#[read_component(Name)]
+#[read_component(FieldOfView)]
+#[read_component(Faction)]
+#[read_component(Health)]
+pub fn overview(
+    ecs: &SubWorld,
+    #[resource] key: &Option<VirtualKeyCode>,
+    #[resource] turn_state: &mut TurnState,
+    #[resource] screen: &mut OverviewScreen,
+    #[resource] bindings: &KeyBindings,
+    #[resource] map: &Map,
+    #[resource] camera: &Camera,
+) {
+    let (player_pos, player_fov) = <(&Point, &FieldOfView)>::query()
+        .filter(component::<Player>())
+        .iter(ecs)
+        .find_map(|(pos, fov)| Some((*pos, fov)))
+        .unwrap();
+    let view = Point::new(
+        camera.width * (GAME_TILE_WIDTH / HUD_TILE_WIDTH) / OVERVIEW_ZOOM,
+        (camera.height * (GAME_TILE_HEIGHT / HUD_TILE_HEIGHT) - MAP_TOP) / OVERVIEW_ZOOM,
+    );
+    let max_offset = Point::new(
+        i32::max(0, map.width - view.x),
+        i32::max(0, map.height - view.y),
+    );
+    let mut offset = screen
+        .offset
+        .unwrap_or_else(|| player_pos - Point::new(view.x / 2, view.y / 2));
+
+    if let Some(key) = key {
+        match (key, bindings.action(*key)) {
+            (VirtualKeyCode::Escape, _) | (_, Some(Action::MapOverview)) => {
+                screen.offset = None;
+                *turn_state = TurnState::AwaitingInput;
+                return;
+            }
+            (_, Some(action)) => {
+                if let Some(step) = action.step() {
+                    offset = offset + step * PAN_STEP;
+                }
+            }
+            _ => (),
+        }
+    }
+    offset = Point::new(
+        offset.x.max(0).min(max_offset.x),
+        offset.y.max(0).min(max_offset.y),
+    );
+    screen.offset = Some(offset);
+
+    let mut draw_batch = DrawBatch::new();
+    draw_batch.target(HUD_LAYER.id);
+    draw_batch.print_color_centered(
+        1,
+        format!(
+            "Dungeon map.  Pan with the movement keys, {} or Escape to close.",
+            bindings.describe(Action::MapOverview)
+        ),
+        ColorPair::new(YELLOW, BLACK),
+    );
+    let to_screen = |pos: Point| (pos - offset) * OVERVIEW_ZOOM + Point::new(0, MAP_TOP);
+    let in_view = |pos: Point| {
+        pos.x >= offset.x
+            && pos.x < offset.x + view.x
+            && pos.y >= offset.y
+            && pos.y < offset.y + view.y
+    };
+    for (y, x) in (offset.y..offset.y + view.y).cartesian_product(offset.x..offset.x + view.x) {
+        let pos = Point::new(x, y);
+        if let Some(tile) = revealed_in_cell(map, pos, 1) {
+            let color = tile_color(tile);
+            for (dy, dx) in (0..OVERVIEW_ZOOM).cartesian_product(0..OVERVIEW_ZOOM) {
+                draw_batch.set(
+                    to_screen(pos) + Point::new(dx, dy),
+                    ColorPair::new(color, color),
+                    to_cp437(' '),
+                );
+            }
+        }
+    }
+    <(&Point, &Name, &Faction)>::query()
+        .filter(component::<Health>() & !component::<Player>())
+        .iter(ecs)
+        .filter(|(pos, _, _)| player_fov.is_visible(pos) && in_view(**pos))
+        .for_each(|(pos, name, faction)| {
+            let color = if faction.is_hostile_to(Faction::Player) {
+                RED
+            } else {
+                CYAN
+            };
+            let glyph = name.0.chars().next().unwrap_or('?').to_ascii_lowercase();
+            draw_batch.set(
+                to_screen(*pos),
+                ColorPair::new(color, BLACK),
+                to_cp437(glyph),
+            );
+        });
+    if in_view(player_pos) {
+        draw_batch.set(
+            to_screen(player_pos),
+            ColorPair::new(YELLOW, BLACK),
+            to_cp437('@'),
+        );
+    }
+    draw_batch.submit(HUD_LAYER.z_order).expect("Batch error");
+}
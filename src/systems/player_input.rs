@@ -1,82 +1,142 @@
 use crate::prelude::*;
+use std::collections::HashMap;
 
 lazy_static! {
-    static ref MOVE_LEFT: Point = Point::new(-1, 0);
-    static ref MOVE_RIGHT: Point = Point::new(1, 0);
-    static ref MOVE_UP: Point = Point::new(0, -1);
-    static ref MOVE_DOWN: Point = Point::new(0, 1);
     static ref DONT_MOVE: Point = Point::zero();
 }
 #[system]
 #[read_component(Point)]
 #[read_component(Player)]
-#[read_component(Enemy)]
 #[write_component(Health)]
+#[read_component(Faction)]
 #[read_component(Item)]
 #[read_component(Carried)]
 #[read_component(Weapon)]
+#[read_component(Equipped)]
+#[read_component(Name)]
+#[read_component(Quantity)]
+#[read_component(Gold)]
+#[read_component(Purse)]
+#[read_component(Merchant)]
 pub fn player_input(
     ecs: &mut SubWorld,
     commands: &mut CommandBuffer,
     #[resource] key: &Option<VirtualKeyCode>,
     #[resource] turn_state: &mut TurnState,
+    #[resource] trade_screen: &mut TradeScreen,
+    #[resource] bindings: &KeyBindings,
+    #[resource] map: &Map,
+    #[resource] mouse_click: &MouseClick,
+    #[resource] travel: &mut TravelPlan,
+    #[resource] log: &mut MessageLog,
 ) {
     let mut players = <(Entity, &Point)>::query().filter(component::<Player>());
-    let mut enemies = <(Entity, &Point)>::query().filter(component::<Enemy>());
+    let mut creatures = <(Entity, &Point, &Faction)>::query().filter(component::<Health>());
 
-    if let Some(key) = key {
-        let mut did_something = false;
+    let (player_entity, player_pos) = players
+        .iter(ecs)
+        .find_map(|(entity, pos)| Some((*entity, *pos)))
+        .unwrap();
+    let clicked_step = if let Some(target) = mouse_click.0 {
+        let step = target - player_pos;
+        if step.x.abs() > 1 || step.y.abs() > 1 {
+            if map
+                .try_idx(target)
+                .map_or(false, |idx| map.revealed_tiles[idx])
+            {
+                *travel = TravelPlan::to(target);
+            }
+            return;
+        }
+        Some(step).filter(|step| *step != *DONT_MOVE)
+    } else {
+        None
+    };
+    if key.is_none() && clicked_step.is_none() {
+        return;
+    }
+    log.clear();
+    let mut did_something = false;
 
-        let delta = match key {
-            VirtualKeyCode::Left => *MOVE_LEFT,
-            VirtualKeyCode::Right => *MOVE_RIGHT,
-            VirtualKeyCode::Up => *MOVE_UP,
-            VirtualKeyCode::Down => *MOVE_DOWN,
-            VirtualKeyCode::G => {
-                let (player, player_pos) = players
-                    .iter(ecs)
-                    .find_map(|(entity, pos)| Some((*entity, *pos)))
-                    .unwrap();
-                let mut items = <(Entity, &Item, &Point)>::query();
-                items
-                    .iter(ecs)
-                    .filter(|(_entity, _item, &item_pos)| item_pos == player_pos)
-                    .for_each(|(entity, _item, _item_pos)| {
-                        commands.remove_component::<Point>(*entity);
-                        commands.add_component(*entity, Carried { by: player });
-                        if let Ok(e) = ecs.entry_ref(*entity) {
-                            if e.get_component::<Weapon>().is_ok() {
-                                <(Entity, &Carried, &Weapon)>::query()
-                                    .iter(ecs)
-                                    .filter(|(_, c, _)| c.by == player)
-                                    .for_each(|(e, _, _)| commands.remove(*e))
-                            }
-                        }
-                    });
-                Point::new(0, 0)
+    let delta = match (clicked_step, (*key).and_then(|key| bindings.action(key))) {
+        (Some(step), _) => step,
+        (None, Some(Action::PickUp)) => {
+            pick_up_items(ecs, commands, player_entity, player_pos);
+            *DONT_MOVE
+        }
+        (None, Some(Action::Search)) => {
+            commands.push((
+                (),
+                WantsToSearch {
+                    searcher: player_entity,
+                },
+            ));
+            *DONT_MOVE
+        }
+        (None, Some(Action::Inventory)) => {
+            *turn_state = TurnState::Inventory;
+            return;
+        }
+        (None, Some(Action::Help)) => {
+            *turn_state = TurnState::Help;
+            return;
+        }
+        (None, Some(Action::Look)) => {
+            *turn_state = TurnState::Look;
+            return;
+        }
+        (None, Some(Action::MapOverview)) => {
+            *turn_state = TurnState::MapOverview;
+            return;
+        }
+        (None, Some(Action::CharacterSheet)) => {
+            *turn_state = TurnState::CharacterSheet;
+            return;
+        }
+        (None, Some(Action::GoToStairs)) => {
+            if let Some(exit) = revealed_exit(map) {
+                *travel = TravelPlan::to(exit);
             }
-            VirtualKeyCode::Key1 => use_item(0, ecs, commands),
-            VirtualKeyCode::Key2 => use_item(1, ecs, commands),
-            VirtualKeyCode::Key3 => use_item(2, ecs, commands),
-            VirtualKeyCode::Key4 => use_item(3, ecs, commands),
-            VirtualKeyCode::Key5 => use_item(4, ecs, commands),
-            VirtualKeyCode::Key6 => use_item(5, ecs, commands),
-            VirtualKeyCode::Key7 => use_item(6, ecs, commands),
-            VirtualKeyCode::Key8 => use_item(7, ecs, commands),
-            VirtualKeyCode::Key9 => use_item(8, ecs, commands),
-            _ => *DONT_MOVE,
-        };
-        let (player_entity, destination) = players
+            return;
+        }
+        (None, Some(Action::Explore)) => {
+            *travel = TravelPlan::explore();
+            return;
+        }
+        (None, Some(Action::Rest)) => {
+            *travel = TravelPlan::rest();
+            return;
+        }
+        (None, Some(Action::UseItem(n))) => use_item(n, ecs, commands),
+        (None, Some(action)) => action.step().unwrap_or(*DONT_MOVE),
+        (None, None) => return,
+    };
+    if !map.can_step(player_pos, delta) {
+        return;
+    }
+    let destination = player_pos + delta;
+    if delta != *DONT_MOVE {
+        let merchant = <(Entity, &Point)>::query()
+            .filter(component::<Merchant>())
+            .iter(ecs)
+            .find(|(_, pos)| **pos == destination)
+            .map(|(entity, _)| *entity);
+        if merchant.is_some() {
+            *trade_screen = TradeScreen {
+                merchant,
+                ..TradeScreen::default()
+            };
+            *turn_state = TurnState::Trade;
+            return;
+        }
+
+        let mut hit_something = false;
+        creatures
             .iter(ecs)
-            .find_map(|(entity, pos)| Some((*entity, *pos + delta)))
-            .unwrap();
-        if delta != *DONT_MOVE {
-            let mut hit_something = false;
-            enemies
-                .iter(ecs)
-                .filter(|(_, pos)| **pos == destination)
-                .for_each(|(entity, _)| {
-                    hit_something = true;
+            .filter(|(_, pos, _)| **pos == destination)
+            .for_each(|(entity, _, faction)| {
+                hit_something = true;
+                if faction.reaction_to(Faction::Player) != Reaction::Allied {
                     did_something = true;
                     commands.push((
                         (),
@@ -85,21 +145,21 @@ pub fn player_input(
                             victim: *entity,
                         },
                     ));
-                });
+                }
+            });
 
-            if !hit_something {
-                commands.push((
-                    (),
-                    WantsToMove {
-                        entity: player_entity,
-                        destination,
-                    },
-                ));
-            }
+        if !hit_something {
+            commands.push((
+                (),
+                WantsToMove {
+                    entity: player_entity,
+                    destination,
+                },
+            ));
         }
-
-        *turn_state = TurnState::PlayerTurn;
     }
+
+    *turn_state = TurnState::PlayerTurn;
 }
 
 fn use_item(n: usize, ecs: &mut SubWorld, commands: &mut CommandBuffer) -> Point {
@@ -126,3 +186,58 @@ fn use_item(n: usize, ecs: &mut SubWorld, commands: &mut CommandBuffer) -> Point
     }
     Point::zero()
 }
+
+pub fn pick_up_items(
+    ecs: &SubWorld,
+    commands: &mut CommandBuffer,
+    player: Entity,
+    player_pos: Point,
+) {
+    let mut stacks: HashMap<String, (Entity, i32)> =
+        <(Entity, &Name, &Quantity, &Carried)>::query()
+            .iter(ecs)
+            .filter(|(_, _, _, carried)| carried.by == player)
+            .map(|(entity, name, quantity, _)| (name.0.clone(), (*entity, quantity.0)))
+            .collect();
+    let mut purse = <&Purse>::query()
+        .filter(component::<Player>())
+        .iter(ecs)
+        .map(|purse| purse.0)
+        .nth(0)
+        .unwrap_or(0);
+    let mut has_weapon = <&Carried>::query()
+        .filter(component::<Weapon>() & component::<Equipped>())
+        .iter(ecs)
+        .any(|c| c.by == player);
+
+    <(Entity, &Item, &Point)>::query()
+        .iter(ecs)
+        .filter(|(_entity, _item, &item_pos)| item_pos == player_pos)
+        .for_each(|(entity, _item, _item_pos)| {
+            let item = ecs.entry_ref(*entity).unwrap();
+            if let Ok(gold) = item.get_component::<Gold>() {
+                purse += gold.0;
+                commands.add_component(player, Purse(purse));
+                commands.remove(*entity);
+                return;
+            }
+            if let (Ok(name), Ok(quantity)) = (
+                item.get_component::<Name>(),
+                item.get_component::<Quantity>(),
+            ) {
+                if let Some((stack, count)) = stacks.get_mut(&name.0) {
+                    *count += quantity.0;
+                    commands.add_component(*stack, Quantity(*count));
+                    commands.remove(*entity);
+                    return;
+                }
+                stacks.insert(name.0.clone(), (*entity, quantity.0));
+            }
+            commands.remove_component::<Point>(*entity);
+            commands.add_component(*entity, Carried { by: player });
+            if item.get_component::<Weapon>().is_ok() && !has_weapon {
+                has_weapon = true;
+                commands.add_component(*entity, Equipped);
+            }
+        });
+}
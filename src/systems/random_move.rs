@@ -4,58 +4,56 @@ use crate::prelude::*;
 #[read_component(Point)]
 #[read_component(MovingRandomly)]
 #[read_component(Health)]
-#[read_component(Player)]
+#[read_component(Energy)]
+#[read_component(Faction)]
 pub fn random_move(
     ecs: &SubWorld,
     commands: &mut CommandBuffer,
     #[resource] rng: &mut RandomNumberGenerator,
+    #[resource] map: &Map,
 ) {
-    let mut movers = <(Entity, &Point, &MovingRandomly)>::query();
+    let mut movers = <(Entity, &Point, &MovingRandomly, &Energy)>::query();
     let mut positions = <(Entity, &Point, &Health)>::query();
 
-    movers.iter(ecs).for_each(|(entity, pos, _)| {
-        let destination = match rng.range(0, 9) {
-            0 => Point::new(-1, -1),
-            1 => Point::new(-1, 0),
-            2 => Point::new(-1, 1),
-            3 => Point::new(0, -1),
-            4 => Point::new(0, 0),
-            5 => Point::new(0, 1),
-            6 => Point::new(1, -1),
-            7 => Point::new(1, 0),
-            _ => Point::new(1, 1),
-        } + *pos;
+    movers
+        .iter(ecs)
+        .filter(|(_, _, _, energy)| energy.can_act())
+        .for_each(|(entity, pos, _, _)| {
+            let steps = map.steps();
+            let delta = match rng.range(0, steps.len() + 1) {
+                n if n < steps.len() => steps[n],
+                _ => Point::zero(),
+            };
+            if !map.can_step(*pos, delta) {
+                return;
+            }
+            let destination = *pos + delta;
 
-        let mut attacked = false;
-        positions
-            .iter(ecs)
-            .filter(|(_, target_pos, _)| **target_pos == destination)
-            .for_each(|(victim, _, _)| {
-                if ecs
-                    .entry_ref(*victim)
-                    .unwrap()
-                    .get_component::<Player>()
-                    .is_ok()
-                {
-                    commands.push((
-                        (),
-                        WantsToAttack {
-                            attacker: *entity,
-                            victim: *victim,
-                        },
-                    ));
-                }
-                attacked = true;
-            });
+            let mut attacked = false;
+            positions
+                .iter(ecs)
+                .filter(|(_, target_pos, _)| **target_pos == destination)
+                .for_each(|(victim, _, _)| {
+                    if reaction_between(ecs, *entity, *victim) == Reaction::Hostile {
+                        commands.push((
+                            (),
+                            WantsToAttack {
+                                attacker: *entity,
+                                victim: *victim,
+                            },
+                        ));
+                    }
+                    attacked = true;
+                });
 
-        if !attacked {
-            commands.push((
-                (),
-                WantsToMove {
-                    entity: *entity,
-                    destination,
-                },
-            ));
-        }
-    });
+            if !attacked {
+                commands.push((
+                    (),
+                    WantsToMove {
+                        entity: *entity,
+                        destination,
+                    },
+                ));
+            }
+        });
 }
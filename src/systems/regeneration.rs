@@ -0,0 +1,38 @@
+use crate::prelude::*;
+
+const REGENERATION_INTERVAL: i32 = 10;
+
+#[system]
+#[read_component(Point)]
+#[read_component(Faction)]
+#[read_component(FieldOfView)]
+#[read_component(Hunger)]
+#[write_component(Health)]
+#[write_component(Regeneration)]
+pub fn regeneration(ecs: &mut SubWorld) {
+    let hostiles: Vec<Point> = <(&Point, &Faction)>::query()
+        .filter(component::<Health>())
+        .iter(ecs)
+        .filter(|(_, faction)| faction.is_hostile_to(Faction::Player))
+        .map(|(pos, _)| *pos)
+        .collect();
+    <(
+        &FieldOfView,
+        &mut Regeneration,
+        &mut Health,
+        Option<&Hunger>,
+    )>::query()
+    .iter_mut(ecs)
+    .for_each(|(fov, regeneration, health, hunger)| {
+        let starving = hunger.map_or(false, |hunger| hunger.satiety <= 0);
+        if starving || hostiles.iter().any(|pos| fov.is_visible(pos)) {
+            regeneration.turns = 0;
+            return;
+        }
+        regeneration.turns += 1;
+        if regeneration.turns >= REGENERATION_INTERVAL {
+            regeneration.turns = 0;
+            health.current = i32::min(health.max, health.current + 1);
+        }
+    });
+}
@@ -0,0 +1,63 @@
+use crate::prelude::*;
+
+const PASSIVE_SEARCH_CHANCE_PERCENT: i32 = 10;
+const ACTIVE_SEARCH_CHANCE_PERCENT: i32 = 75;
+
+#[system]
+#[read_component(WantsToSearch)]
+#[read_component(Player)]
+#[read_component(Point)]
+#[read_component(FieldOfView)]
+#[read_component(Trap)]
+#[read_component(Hidden)]
+pub fn search(
+    ecs: &SubWorld,
+    commands: &mut CommandBuffer,
+    #[resource] map: &mut Map,
+    #[resource] rng: &mut RandomNumberGenerator,
+) {
+    let searchers: Vec<Entity> = <(Entity, &WantsToSearch)>::query()
+        .iter(ecs)
+        .map(|(message, search)| {
+            commands.remove(*message);
+            search.searcher
+        })
+        .collect();
+
+    let mut found_any = false;
+    <(Entity, &Point)>::query()
+        .filter(component::<Player>())
+        .iter(ecs)
+        .for_each(|(player, pos)| {
+            let chance = if searchers.contains(player) {
+                ACTIVE_SEARCH_CHANCE_PERCENT
+            } else {
+                PASSIVE_SEARCH_CHANCE_PERCENT
+            };
+            <(Entity, &Point)>::query()
+                .filter(component::<Trap>() & component::<Hidden>())
+                .iter(ecs)
+                .filter(|(_, trap_pos)| DistanceAlg::Pythagoras.distance2d(*pos, **trap_pos) < 1.5)
+                .for_each(|(trap, trap_pos)| {
+                    if rng.range(0, 100) < chance {
+                        commands.remove_component::<Hidden>(*trap);
+                        map.add_hazard(map.point2d_to_index(*trap_pos));
+                    }
+                });
+            for (dy, dx) in (-1..=1).cartesian_product(-1..=1) {
+                if let Some(idx) = map.try_idx(*pos + Point::new(dx, dy)) {
+                    if map.tiles[idx] == TileType::SecretDoor && rng.range(0, 100) < chance {
+                        map.set_tile(idx, TileType::Door);
+                        map.revealed_tiles[idx] = true;
+                        found_any = true;
+                    }
+                }
+            }
+        });
+
+    if found_any {
+        <(Entity, &FieldOfView)>::query()
+            .iter(ecs)
+            .for_each(|(viewer, fov)| commands.add_component(*viewer, fov.clone_dirty()));
+    }
+}
@@ -0,0 +1,19 @@
+use crate::prelude::*;
+
+#[system(for_each)]
+pub fn statistics(
+    entity: &Entity,
+    slain: &Slain,
+    commands: &mut CommandBuffer,
+    #[resource] statistics: &mut Statistics,
+) {
+    statistics.record_kill(&slain.name);
+    commands.remove(*entity);
+}
+
+#[system]
+pub fn count_turns(#[resource] turn_state: &TurnState, #[resource] statistics: &mut Statistics) {
+    if *turn_state == TurnState::PlayerTurn {
+        statistics.turns += 1;
+    }
+}
@@ -0,0 +1,77 @@
+use crate::prelude::*;
+
+#[system]
+#[read_component(WantsToThrow)]
+#[read_component(Point)]
+#[read_component(Player)]
+#[read_component(Damage)]
+#[read_component(ProvidesHealing)]
+#[read_component(ProvidesFire)]
+#[read_component(ProvidesGas)]
+#[read_component(Quantity)]
+#[read_component(DropsGold)]
+#[read_component(Name)]
+#[read_component(Enemy)]
+#[write_component(Health)]
+pub fn throwing(
+    ecs: &mut SubWorld,
+    commands: &mut CommandBuffer,
+    #[resource] map: &Map,
+    #[resource] environment: &mut Environment,
+) {
+    let throws: Vec<(Entity, WantsToThrow)> = <(Entity, &WantsToThrow)>::query()
+        .iter(ecs)
+        .map(|(entity, throw)| (*entity, *throw))
+        .collect();
+
+    throws.iter().for_each(|(message, throw)| {
+        let (damage, healing, quantity, shatters, name) =
+            if let Ok(item) = ecs.entry_ref(throw.item) {
+                let fire = item.get_component::<ProvidesFire>().map(|fire| fire.radius);
+                let gas = item.get_component::<ProvidesGas>().map(|gas| gas.radius);
+                if let Ok(radius) = fire {
+                    environment.ignite(map, throw.target, radius);
+                }
+                if let Ok(radius) = gas {
+                    environment.release_gas(map, throw.target, radius);
+                }
+                (
+                    item.get_component::<Damage>().map_or(0, |dmg| dmg.0),
+                    item.get_component::<ProvidesHealing>()
+                        .map_or(0, |healing| healing.amount),
+                    item.get_component::<Quantity>()
+                        .map_or(1, |quantity| quantity.0),
+                    fire.is_ok() || gas.is_ok(),
+                    item.get_component::<Name>().ok().map(|name| name.0.clone()),
+                )
+            } else {
+                (0, 0, 1, false, None)
+            };
+
+        let victim = <(Entity, &Point)>::query()
+            .filter(component::<Health>())
+            .iter(ecs)
+            .find(|(_, pos)| **pos == throw.target)
+            .map(|(entity, _)| *entity);
+
+        let used_up =
+            shatters || map.swallows_items(throw.target) || (healing > 0 && victim.is_some());
+        if quantity > 1 {
+            commands.add_component(throw.item, Quantity(quantity - 1));
+            if let (Some(name), false) = (&name, used_up) {
+                spawn_item(commands, name, throw.target);
+            }
+        } else if used_up {
+            commands.remove(throw.item);
+        } else {
+            commands.remove_component::<Carried>(throw.item);
+            commands.remove_component::<Equipped>(throw.item);
+            commands.add_component(throw.item, throw.target);
+        }
+
+        if let Some(victim) = victim {
+            super::combat::damage(ecs, commands, victim, damage - healing, Some(throw.thrower));
+        }
+        commands.remove(*message);
+    });
+}
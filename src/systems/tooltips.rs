@@ -6,8 +6,14 @@ use crate::prelude::*;
 #[read_component(Health)]
 #[read_component(FieldOfView)]
 #[read_component(Player)]
-pub fn tooltips(ecs: &SubWorld, #[resource] mouse_pos: &Point, #[resource] camera: &Camera) {
-    let mut positions = <(Entity, &Point, &Name)>::query();
+#[read_component(Hidden)]
+pub fn tooltips(
+    ecs: &SubWorld,
+    #[resource] mouse_pos: &Point,
+    #[resource] camera: &Camera,
+    #[resource] identification: &Identification,
+) {
+    let mut positions = <(Entity, &Point, &Name)>::query().filter(!component::<Hidden>());
     let mut fov = <&FieldOfView>::query().filter(component::<Player>());
     let offset = Point::new(camera.left_x, camera.top_y);
     let map_pos = *mouse_pos + offset;
@@ -23,10 +29,11 @@ pub fn tooltips(ecs: &SubWorld, #[resource] mouse_pos: &Point, #[resource] camer
         .for_each(|(entity, _, name)| {
             let screen_pos = (*mouse_pos * (GAME_TILE_WIDTH / HUD_TILE_WIDTH)) - 1;
 
+            let name = identification.display_name(&name.0);
             if let Ok(health) = ecs.entry_ref(*entity).unwrap().get_component::<Health>() {
-                draw_batch.print(screen_pos, format!("{} : {} hp", &name.0, health.current));
+                draw_batch.print(screen_pos, format!("{} : {} hp", name, health.current));
             } else {
-                draw_batch.print(screen_pos, &name.0);
+                draw_batch.print(screen_pos, name);
             };
             draw_batch
                 .submit(ENTITY_LAYER.z_order + 100)
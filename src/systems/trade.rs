@@ -0,0 +1,192 @@
+use crate::prelude::*;
+
+const LIST_TOP: i32 = 6;
+const LIST_COLUMN: i32 = 4;
+const PRICE_COLUMN: i32 = 60;
+const PRICE_PERCENT_PER_LEVEL: i32 = 50;
+const SELL_PERCENT: i32 = 50;
+
+#[system]
+#[read_component(Player)]
+#[read_component(Item)]
+#[read_component(Carried)]
+#[read_component(Name)]
+#[read_component(Equipped)]
+#[read_component(Quantity)]
+#[read_component(Charges)]
+#[read_component(Value)]
+#[read_component(Purse)]
+pub fn trade(
+    ecs: &SubWorld,
+    commands: &mut CommandBuffer,
+    #[resource] key: &Option<VirtualKeyCode>,
+    #[resource] turn_state: &mut TurnState,
+    #[resource] screen: &mut TradeScreen,
+    #[resource] bindings: &KeyBindings,
+    #[resource] identification: &Identification,
+) {
+    let merchant = match screen.merchant {
+        Some(merchant) if ecs.entry_ref(merchant).is_ok() => merchant,
+        _ => {
+            *turn_state = TurnState::AwaitingInput;
+            return;
+        }
+    };
+    let (player, map_level, purse) = <(Entity, &Player, &Purse)>::query()
+        .iter(ecs)
+        .find_map(|(entity, player, purse)| Some((*entity, player.map_level as i32, purse.0)))
+        .unwrap();
+    let owner = match screen.mode {
+        TradeMode::Buy => merchant,
+        TradeMode::Sell => player,
+    };
+    let items: Vec<(Entity, i32)> = <(Entity, &Item, &Carried, &Value)>::query()
+        .iter(ecs)
+        .filter(|(_, _, carried, _)| carried.by == owner)
+        .map(|(entity, _, _, value)| {
+            let quantity = ecs
+                .entry_ref(*entity)
+                .unwrap()
+                .get_component::<Quantity>()
+                .map_or(1, |quantity| quantity.0);
+            let price = match screen.mode {
+                TradeMode::Buy => buy_price(value.0, map_level),
+                TradeMode::Sell => sell_price(value.0, map_level),
+            };
+            (*entity, price * quantity)
+        })
+        .collect();
+    let last_item = items.len().saturating_sub(1);
+    screen.selected = screen.selected.min(last_item);
+
+    if let Some(key) = key {
+        match (key, bindings.action(*key)) {
+            (VirtualKeyCode::Escape, _) => *turn_state = TurnState::AwaitingInput,
+            (VirtualKeyCode::Tab, _) | (_, Some(Action::MoveLeft | Action::MoveRight)) => {
+                screen.mode = match screen.mode {
+                    TradeMode::Buy => TradeMode::Sell,
+                    TradeMode::Sell => TradeMode::Buy,
+                };
+                screen.selected = 0;
+            }
+            (VirtualKeyCode::Return, _) => {
+                if let Some((item, price)) = items.get(screen.selected) {
+                    match screen.mode {
+                        TradeMode::Buy if *price <= purse => {
+                            commands.add_component(player, Purse(purse - price));
+                            transfer(ecs, commands, *item, player);
+                        }
+                        TradeMode::Sell => {
+                            commands.add_component(player, Purse(purse + price));
+                            transfer(ecs, commands, *item, merchant);
+                        }
+                        _ => (),
+                    }
+                }
+            }
+            (_, Some(Action::MoveUp)) => screen.selected = screen.selected.saturating_sub(1),
+            (_, Some(Action::MoveDown)) => screen.selected = (screen.selected + 1).min(last_item),
+            _ => (),
+        }
+    }
+
+    let mut draw_batch = DrawBatch::new();
+    draw_batch.target(HUD_LAYER.id);
+    draw_batch.print_color_centered(1, "Merchant", ColorPair::new(YELLOW, BLACK));
+    let (buy_color, sell_color) = match screen.mode {
+        TradeMode::Buy => (YELLOW, GRAY),
+        TradeMode::Sell => (GRAY, YELLOW),
+    };
+    draw_batch.print_color(
+        Point::new(LIST_COLUMN, 3),
+        "Buy",
+        ColorPair::new(buy_color, BLACK),
+    );
+    draw_batch.print_color(
+        Point::new(LIST_COLUMN + 6, 3),
+        "Sell",
+        ColorPair::new(sell_color, BLACK),
+    );
+    draw_batch.print_color_right(
+        Point::new(PRICE_COLUMN, 3),
+        format!("Gold: {}", purse),
+        ColorPair::new(GOLD, BLACK),
+    );
+    if items.is_empty() {
+        draw_batch.print(
+            Point::new(LIST_COLUMN, LIST_TOP),
+            match screen.mode {
+                TradeMode::Buy => "The merchant has nothing left to sell.",
+                TradeMode::Sell => "You have nothing the merchant wants.",
+            },
+        );
+    }
+    items.iter().enumerate().for_each(|(n, (item, price))| {
+        let entry = ecs.entry_ref(*item).unwrap();
+        let (marker, color) = if n == screen.selected {
+            (">", YELLOW)
+        } else {
+            (" ", WHITE)
+        };
+        let price_color = if screen.mode == TradeMode::Buy && *price > purse {
+            RED
+        } else {
+            GOLD
+        };
+        let y = LIST_TOP + n as i32;
+        draw_batch.print_color(
+            Point::new(LIST_COLUMN, y),
+            format!("{} {}", marker, item_label(&entry, identification)),
+            ColorPair::new(color, BLACK),
+        );
+        draw_batch.print_color_right(
+            Point::new(PRICE_COLUMN, y),
+            format!("{} gold", price),
+            ColorPair::new(price_color, BLACK),
+        );
+    });
+    draw_batch.print_color(
+        Point::new(LIST_COLUMN, LIST_TOP + items.len().max(1) as i32 + 2),
+        "[Enter] Trade  [Tab] Buy / Sell  [Esc] Leave",
+        ColorPair::new(GREEN, BLACK),
+    );
+    draw_batch.submit(HUD_LAYER.z_order).expect("Batch error");
+}
+
+fn buy_price(value: i32, map_level: i32) -> i32 {
+    value * (100 + map_level * PRICE_PERCENT_PER_LEVEL) / 100
+}
+
+fn sell_price(value: i32, map_level: i32) -> i32 {
+    i32::max(1, buy_price(value, map_level) * SELL_PERCENT / 100)
+}
+
+fn transfer(ecs: &SubWorld, commands: &mut CommandBuffer, item: Entity, to: Entity) {
+    let entry = ecs.entry_ref(item).unwrap();
+    if let (Ok(name), Ok(quantity)) = (
+        entry.get_component::<Name>(),
+        entry.get_component::<Quantity>(),
+    ) {
+        let stack = <(Entity, &Name, &Quantity, &Carried)>::query()
+            .iter(ecs)
+            .find(|(other, other_name, _, carried)| {
+                carried.by == to && **other != item && other_name.0 == name.0
+            })
+            .map(|(other, _, other_quantity, _)| (*other, other_quantity.0));
+        if let Some((stack, count)) = stack {
+            commands.add_component(stack, Quantity(count + quantity.0));
+            commands.remove(item);
+            return;
+        }
+    }
+    commands.remove_component::<Equipped>(item);
+    commands.add_component(item, Carried { by: to });
+}
+
+#[test]
+fn prices_rise_with_dungeon_level() {
+    assert_eq!(buy_price(10, 0), 10);
+    assert_eq!(buy_price(10, 2), 20);
+    assert_eq!(sell_price(10, 0), 5);
+    assert_eq!(sell_price(1, 0), 1);
+}
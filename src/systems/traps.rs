@@ -0,0 +1,127 @@
+use crate::prelude::*;
+
+const MAX_TELEPORT_ATTEMPTS: usize = 100;
+const ALARM_SUMMONS: usize = 3;
+
+#[system]
+#[read_component(TriggerTrap)]
+#[read_component(Trap)]
+#[read_component(Point)]
+#[read_component(Player)]
+#[read_component(Enemy)]
+#[read_component(FieldOfView)]
+#[read_component(DropsGold)]
+#[read_component(Name)]
+#[write_component(Health)]
+pub fn traps(
+    ecs: &mut SubWorld,
+    commands: &mut CommandBuffer,
+    #[resource] map: &mut Map,
+    #[resource] camera: &mut Camera,
+    #[resource] rng: &mut RandomNumberGenerator,
+) {
+    let triggers: Vec<(Entity, TriggerTrap)> = <(Entity, &TriggerTrap)>::query()
+        .iter(ecs)
+        .map(|(entity, trigger)| (*entity, *trigger))
+        .collect();
+
+    triggers.iter().for_each(|(message, trigger)| {
+        commands.remove(*message);
+        let (kind, trap_pos) = match ecs.entry_ref(trigger.trap) {
+            Ok(trap) => (
+                trap.get_component::<Trap>().unwrap().kind,
+                *trap.get_component::<Point>().unwrap(),
+            ),
+            Err(_) => return,
+        };
+        let is_player = ecs
+            .entry_ref(trigger.victim)
+            .map_or(false, |victim| victim.get_component::<Player>().is_ok());
+
+        commands.remove_component::<Hidden>(trigger.trap);
+        let trap_idx = map.point2d_to_index(trap_pos);
+        map.add_hazard(trap_idx);
+        map.revealed_tiles[trap_idx] = true;
+
+        match kind {
+            TrapKind::Spikes(damage) => {
+                super::combat::damage(ecs, commands, trigger.victim, damage, None);
+            }
+            TrapKind::Teleport => {
+                if let Some(destination) = random_open_tile(ecs, map, rng) {
+                    move_to(ecs, commands, trigger.victim, destination);
+                    if is_player {
+                        camera.on_player_move(destination);
+                    }
+                }
+            }
+            TrapKind::Alarm => {
+                summon_monsters(ecs, commands, map, trap_pos);
+                map.remove_hazard(map.point2d_to_index(trap_pos));
+                commands.remove(trigger.trap);
+            }
+            TrapKind::Trapdoor => {
+                if !is_player {
+                    commands.remove(trigger.victim);
+                }
+            }
+        }
+    });
+}
+
+fn is_occupied(ecs: &SubWorld, pos: Point) -> bool {
+    <&Point>::query()
+        .filter(component::<Health>())
+        .iter(ecs)
+        .any(|occupied| *occupied == pos)
+}
+
+fn random_open_tile(ecs: &SubWorld, map: &Map, rng: &mut RandomNumberGenerator) -> Option<Point> {
+    (0..MAX_TELEPORT_ATTEMPTS)
+        .map(|_| Point::new(rng.range(0, map.width), rng.range(0, map.height)))
+        .find(|pos| {
+            map.is_safe_floor(*pos)
+                && !map.hazards.contains(&map.point2d_to_index(*pos))
+                && !is_occupied(ecs, *pos)
+        })
+}
+
+fn move_to(ecs: &SubWorld, commands: &mut CommandBuffer, entity: Entity, destination: Point) {
+    commands.add_component(entity, destination);
+    if let Ok(fov) = ecs
+        .entry_ref(entity)
+        .unwrap()
+        .get_component::<FieldOfView>()
+    {
+        commands.add_component(entity, fov.clone_dirty());
+    }
+}
+
+fn summon_monsters(ecs: &SubWorld, commands: &mut CommandBuffer, map: &Map, trap_pos: Point) {
+    let mut free_tiles: Vec<Point> = (-1..=1)
+        .cartesian_product(-1..=1)
+        .map(|(dy, dx)| trap_pos + Point::new(dx, dy))
+        .filter(|pos| *pos != trap_pos && map.is_safe_floor(*pos) && !is_occupied(ecs, *pos))
+        .collect();
+    let mut monsters: Vec<(Entity, Point)> = <(Entity, &Point)>::query()
+        .filter(component::<Enemy>())
+        .iter(ecs)
+        .map(|(entity, pos)| (*entity, *pos))
+        .collect();
+    monsters.sort_by(|(_, a), (_, b)| {
+        let distance_a = DistanceAlg::Pythagoras.distance2d(trap_pos, *a);
+        let distance_b = DistanceAlg::Pythagoras.distance2d(trap_pos, *b);
+        distance_a.partial_cmp(&distance_b).unwrap()
+    });
+    let summoned: Vec<Entity> = monsters
+        .iter()
+        .filter(|(_, pos)| !free_tiles.contains(pos))
+        .take(ALARM_SUMMONS)
+        .map(|(monster, _)| *monster)
+        .collect();
+    summoned.iter().for_each(|monster| {
+        if let Some(destination) = free_tiles.pop() {
+            move_to(ecs, commands, *monster, destination);
+        }
+    });
+}
@@ -0,0 +1,132 @@
+use crate::prelude::*;
+use std::collections::{HashMap, HashSet};
+
+#[system]
+#[read_component(Point)]
+#[read_component(Player)]
+#[read_component(Health)]
+#[read_component(Faction)]
+#[read_component(FieldOfView)]
+#[read_component(Item)]
+#[read_component(Carried)]
+#[read_component(Weapon)]
+#[read_component(Equipped)]
+#[read_component(Name)]
+#[read_component(Quantity)]
+#[read_component(Gold)]
+#[read_component(Purse)]
+pub fn travel(
+    ecs: &mut SubWorld,
+    commands: &mut CommandBuffer,
+    #[resource] key: &Option<VirtualKeyCode>,
+    #[resource] turn_state: &mut TurnState,
+    #[resource] plan: &mut TravelPlan,
+    #[resource] map: &Map,
+    #[resource] config: &Config,
+    #[resource] log: &mut MessageLog,
+    #[resource] bindings: &KeyBindings,
+) {
+    if !plan.is_active() {
+        return;
+    }
+    if key.is_some() {
+        plan.cancel();
+        return;
+    }
+    let (player, player_pos, health, max_health, fov) =
+        <(Entity, &Point, &Health, &FieldOfView)>::query()
+            .filter(component::<Player>())
+            .iter(ecs)
+            .find_map(|(entity, pos, health, fov)| {
+                Some((*entity, *pos, health.current, health.max, fov))
+            })
+            .unwrap();
+
+    let mut creatures = <(Entity, &Point, &Faction)>::query().filter(component::<Health>());
+    let hostiles: HashSet<Entity> = creatures
+        .iter(ecs)
+        .filter(|(_, pos, faction)| faction.is_hostile_to(Faction::Player) && fov.is_visible(pos))
+        .map(|(entity, _, _)| *entity)
+        .collect();
+    let underfoot = <&Point>::query()
+        .filter(component::<Item>())
+        .iter(ecs)
+        .any(|pos| *pos == player_pos);
+
+    let started = plan.health.is_some();
+    let hurt = plan
+        .health
+        .map_or(false, |last_health| health < last_health);
+    let spotted = started && hostiles.iter().any(|hostile| !plan.seen.contains(hostile));
+    if hurt || spotted {
+        plan.cancel();
+        return;
+    }
+    if plan.resting {
+        if !hostiles.is_empty() || health >= max_health {
+            log.add(if hostiles.is_empty() {
+                "You feel rested."
+            } else {
+                "You can't rest with enemies in view."
+            });
+            plan.cancel();
+            return;
+        }
+    } else if underfoot && config.auto_pickup {
+        super::player_input::pick_up_items(ecs, commands, player, player_pos);
+    } else if underfoot && started {
+        plan.cancel();
+        return;
+    } else {
+        let bystanders: HashMap<usize, Reaction> = creatures
+            .iter(ecs)
+            .filter(|(entity, _, _)| **entity != player)
+            .map(|(_, pos, faction)| {
+                (
+                    map.point2d_to_index(*pos),
+                    faction.reaction_to(Faction::Player),
+                )
+            })
+            .filter(|(_, reaction)| *reaction != Reaction::Hostile)
+            .collect();
+        let next_step = match plan.destination {
+            Some(destination) => next_travel_step(map, player_pos, destination, &bystanders),
+            None => {
+                plan.visited.insert(map.point2d_to_index(player_pos));
+                next_explore_step(map, player_pos, &bystanders, &plan.visited)
+            }
+        };
+        let step = match next_step {
+            Some(step)
+                if !creatures.iter(ecs).any(|(_, pos, faction)| {
+                    *pos == step && faction.is_hostile_to(Faction::Player)
+                }) =>
+            {
+                step
+            }
+            _ => {
+                if plan.exploring && next_step.is_none() {
+                    log.add(match revealed_exit(map) {
+                        Some(_) => format!(
+                            "Explored. Press {} to travel to the exit.",
+                            bindings.describe(Action::GoToStairs)
+                        ),
+                        None => String::from("Explored."),
+                    });
+                }
+                plan.cancel();
+                return;
+            }
+        };
+        commands.push((
+            (),
+            WantsToMove {
+                entity: player,
+                destination: step,
+            },
+        ));
+    }
+    plan.health = Some(health);
+    plan.seen = hostiles;
+    *turn_state = TurnState::PlayerTurn;
+}
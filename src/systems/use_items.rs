@@ -1,12 +1,48 @@
 use crate::prelude::*;
+use legion::world::EntryRef;
+
+const BLAST_DAMAGE: i32 = 4;
 
 #[system]
 #[read_component(ActivateItem)]
 #[read_component(ProvidesHealing)]
 #[read_component(ProvidesDungeonMap)]
+#[read_component(ProvidesCharm)]
+#[read_component(Point)]
+#[read_component(FieldOfView)]
+#[read_component(Faction)]
+#[read_component(Weapon)]
+#[read_component(Quantity)]
+#[read_component(Charges)]
+#[read_component(ProvidesIdentify)]
+#[read_component(Name)]
+#[read_component(Carried)]
+#[read_component(ProvidesNutrition)]
+#[read_component(ProvidesDigging)]
+#[read_component(ProvidesBlast)]
+#[read_component(ProvidesFire)]
+#[read_component(ProvidesGas)]
+#[read_component(ProvidesFuel)]
+#[read_component(LightSource)]
+#[read_component(Player)]
+#[read_component(DropsGold)]
+#[read_component(Enemy)]
 #[write_component(Health)]
-pub fn use_items(ecs: &mut SubWorld, commands: &mut CommandBuffer, #[resource] map: &mut Map) {
+#[write_component(Hunger)]
+#[write_component(Lantern)]
+pub fn use_items(
+    ecs: &mut SubWorld,
+    commands: &mut CommandBuffer,
+    #[resource] map: &mut Map,
+    #[resource] identification: &mut Identification,
+    #[resource] environment: &mut Environment,
+) {
     let mut healing_to_apply = Vec::<(Entity, i32)>::new();
+    let mut charms_to_apply = Vec::<(Entity, Faction)>::new();
+    let mut nutrition_to_apply = Vec::<(Entity, i32)>::new();
+    let mut fuel_to_apply = Vec::<(Entity, i32)>::new();
+    let mut blast_victims = Vec::<(Entity, Entity)>::new();
+    let mut map_changed = false;
 
     <(Entity, &ActivateItem)>::query()
         .iter(ecs)
@@ -17,15 +53,119 @@ pub fn use_items(ecs: &mut SubWorld, commands: &mut CommandBuffer, #[resource] m
                     healing_to_apply.push((activate.used_by, healing.amount));
                 }
 
+                if let Ok(nutrition) = item.get_component::<ProvidesNutrition>() {
+                    nutrition_to_apply.push((activate.used_by, nutrition.amount));
+                }
+
+                if let Ok(fuel) = item.get_component::<ProvidesFuel>() {
+                    fuel_to_apply.push((activate.used_by, fuel.amount));
+                }
+
                 if let Ok(_mapper) = item.get_component::<ProvidesDungeonMap>() {
                     map.revealed_tiles.iter_mut().for_each(|t| *t = true);
+                    for idx in 0..map.tiles.len() {
+                        if map.tiles[idx] == TileType::SecretDoor {
+                            map.set_tile(idx, TileType::Door);
+                        }
+                    }
+                }
+
+                if let Ok(blast) = item.get_component::<ProvidesBlast>() {
+                    if let Ok(user) = ecs.entry_ref(activate.used_by) {
+                        if let Ok(center) = user.get_component::<Point>() {
+                            for (dy, dx) in (-blast.radius..=blast.radius)
+                                .cartesian_product(-blast.radius..=blast.radius)
+                            {
+                                map_changed |= map.dig(*center + Point::new(dx, dy));
+                            }
+                            <(Entity, &Point)>::query()
+                                .filter(component::<Health>())
+                                .iter(ecs)
+                                .filter(|(victim, pos)| {
+                                    **victim != activate.used_by
+                                        && (pos.x - center.x).abs() <= blast.radius
+                                        && (pos.y - center.y).abs() <= blast.radius
+                                })
+                                .for_each(|(victim, _)| {
+                                    blast_victims.push((activate.used_by, *victim))
+                                });
+                        }
+                    }
+                }
+
+                if let Some(user_pos) = ecs
+                    .entry_ref(activate.used_by)
+                    .ok()
+                    .and_then(|user| user.get_component::<Point>().ok().copied())
+                {
+                    if let Ok(fire) = item.get_component::<ProvidesFire>() {
+                        environment.ignite(map, user_pos, fire.radius);
+                    }
+                    if let Ok(gas) = item.get_component::<ProvidesGas>() {
+                        environment.release_gas(map, user_pos, gas.radius);
+                    }
+                }
+
+                if let Ok(_charm) = item.get_component::<ProvidesCharm>() {
+                    if let Some(charm) = charm_target(ecs, activate.used_by) {
+                        charms_to_apply.push(charm);
+                    }
+                }
+
+                if let Ok(_identify) = item.get_component::<ProvidesIdentify>() {
+                    <(&Name, &Carried)>::query()
+                        .iter(ecs)
+                        .filter(|(_, carried)| carried.by == activate.used_by)
+                        .for_each(|(name, _)| identification.identify(&name.0));
+                }
+
+                if let Ok(name) = item.get_component::<Name>() {
+                    identification.identify(&name.0);
+                }
+
+                if item.get_component::<Weapon>().is_err()
+                    && item.get_component::<ProvidesDigging>().is_err()
+                    && item.get_component::<LightSource>().is_err()
+                {
+                    consume(&item, activate.item, commands);
                 }
             }
 
-            commands.remove(activate.item);
             commands.remove(*entity);
         });
 
+    for (target, faction) in charms_to_apply {
+        commands.add_component(target, faction);
+        commands.remove_component::<ChasingPlayer>(target);
+        commands.add_component(target, FollowingPlayer);
+    }
+
+    for (eater, amount) in nutrition_to_apply {
+        if let Ok(mut eater) = ecs.entry_mut(eater) {
+            if let Ok(hunger) = eater.get_component_mut::<Hunger>() {
+                hunger.eat(amount);
+            }
+        }
+    }
+
+    for (user, amount) in fuel_to_apply {
+        if let Ok(mut user) = ecs.entry_mut(user) {
+            if let Ok(lantern) = user.get_component_mut::<Lantern>() {
+                lantern.refuel(amount);
+            }
+        }
+    }
+
+    if map_changed {
+        <(Entity, &FieldOfView)>::query()
+            .iter(ecs)
+            .for_each(|(viewer, fov)| commands.add_component(*viewer, fov.clone_dirty()));
+    }
+
+    for (user, victim) in blast_victims {
+        super::combat::damage(ecs, commands, victim, BLAST_DAMAGE, Some(user));
+    }
+
     for heal in healing_to_apply.iter() {
         if let Ok(mut target) = ecs.entry_mut(heal.0) {
             if let Ok(health) = target.get_component_mut::<Health>() {
@@ -34,3 +174,44 @@ pub fn use_items(ecs: &mut SubWorld, commands: &mut CommandBuffer, #[resource] m
         }
     }
 }
+
+fn consume(item: &EntryRef, item_entity: Entity, commands: &mut CommandBuffer) {
+    if let Ok(charges) = item.get_component::<Charges>() {
+        if charges.current > 1 {
+            commands.add_component(
+                item_entity,
+                Charges {
+                    current: charges.current - 1,
+                    max: charges.max,
+                },
+            );
+            return;
+        }
+    }
+    if let Ok(Quantity(n)) = item.get_component::<Quantity>() {
+        if *n > 1 {
+            commands.add_component(item_entity, Quantity(n - 1));
+            return;
+        }
+    }
+    commands.remove(item_entity);
+}
+
+fn charm_target(ecs: &SubWorld, user: Entity) -> Option<(Entity, Faction)> {
+    let user = ecs.entry_ref(user).ok()?;
+    let user_pos = *user.get_component::<Point>().ok()?;
+    let user_fov = user.get_component::<FieldOfView>().ok()?;
+    let user_faction = *user.get_component::<Faction>().ok()?;
+    <(Entity, &Point, &Faction)>::query()
+        .filter(component::<Health>())
+        .iter(ecs)
+        .filter(|(_, pos, faction)| {
+            user_fov.is_visible(pos) && faction.reaction_to(user_faction) != Reaction::Allied
+        })
+        .min_by(|(_, a, _), (_, b, _)| {
+            let distance_a = DistanceAlg::Pythagoras.distance2d(user_pos, **a);
+            let distance_b = DistanceAlg::Pythagoras.distance2d(user_pos, **b);
+            distance_a.partial_cmp(&distance_b).unwrap()
+        })
+        .map(|(entity, _, _)| (*entity, user_faction))
+}
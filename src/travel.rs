@@ -0,0 +1,218 @@
+use crate::prelude::*;
+use std::collections::{HashMap, HashSet};
+
+const ALLY_PATH_COST: f32 = 5.0;
+
+#[derive(Clone, Debug, Default)]
+pub struct TravelPlan {
+    pub destination: Option<Point>,
+    pub exploring: bool,
+    pub resting: bool,
+    pub health: Option<i32>,
+    pub seen: HashSet<Entity>,
+    pub visited: HashSet<usize>,
+}
+
+impl TravelPlan {
+    pub fn to(destination: Point) -> Self {
+        Self {
+            destination: Some(destination),
+            ..Self::default()
+        }
+    }
+
+    pub fn explore() -> Self {
+        Self {
+            exploring: true,
+            ..Self::default()
+        }
+    }
+
+    pub fn rest() -> Self {
+        Self {
+            resting: true,
+            ..Self::default()
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.exploring || self.resting || self.destination.is_some()
+    }
+
+    pub fn cancel(&mut self) {
+        *self = Self::default();
+    }
+}
+
+struct RevealedMap<'a> {
+    map: &'a Map,
+    bystanders: &'a HashMap<usize, Reaction>,
+}
+
+impl BaseMap for RevealedMap<'_> {
+    fn get_available_exits(&self, idx: usize) -> SmallVec<[(usize, f32); 10]> {
+        TrapAwareMap::new(self.map, &self.map.hazards)
+            .get_available_exits(idx)
+            .into_iter()
+            .filter(|(exit, _)| {
+                self.map.revealed_tiles[*exit] && self.map.tiles[*exit] != TileType::Lava
+            })
+            .filter_map(|(exit, cost)| match self.bystanders.get(&exit) {
+                None => Some((exit, cost)),
+                Some(Reaction::Allied) => Some((exit, cost + ALLY_PATH_COST)),
+                Some(_) => None,
+            })
+            .collect()
+    }
+
+    fn get_pathing_distance(&self, idx1: usize, idx2: usize) -> f32 {
+        self.map.get_pathing_distance(idx1, idx2)
+    }
+}
+
+pub fn next_travel_step(
+    map: &Map,
+    from: Point,
+    to: Point,
+    bystanders: &HashMap<usize, Reaction>,
+) -> Option<Point> {
+    let target = map.try_idx(to)?;
+    if from == to || !map.revealed_tiles[target] {
+        return None;
+    }
+    let path = a_star_search(
+        map.point2d_to_index(from),
+        target,
+        &RevealedMap { map, bystanders },
+    );
+    if path.success && path.steps.len() > 1 {
+        Some(map.index_to_point2d(path.steps[1]))
+    } else {
+        None
+    }
+}
+
+fn is_frontier(map: &Map, idx: usize) -> bool {
+    let pos = map.index_to_point2d(idx);
+    map.revealed_tiles[idx]
+        && map.can_path_through(pos)
+        && map.steps().iter().any(|delta| {
+            map.try_idx(pos + *delta)
+                .map_or(false, |next| !map.revealed_tiles[next])
+        })
+}
+
+pub fn next_explore_step(
+    map: &Map,
+    from: Point,
+    bystanders: &HashMap<usize, Reaction>,
+    visited: &HashSet<usize>,
+) -> Option<Point> {
+    let start = map.point2d_to_index(from);
+    let frontier: Vec<usize> = (0..map.tiles.len())
+        .filter(|idx| *idx != start && !visited.contains(idx) && is_frontier(map, *idx))
+        .collect();
+    if frontier.is_empty() {
+        return None;
+    }
+    let revealed = RevealedMap { map, bystanders };
+    let dijkstra_map = DijkstraMap::new(
+        map.width,
+        map.height,
+        &frontier,
+        &revealed,
+        DISTANCE_MAX_DEPTH,
+    );
+    if dijkstra_map.map[start] == UNREACHABLE {
+        return None;
+    }
+    DijkstraMap::find_lowest_exit(&dijkstra_map, start, &revealed)
+        .map(|idx| map.index_to_point2d(idx))
+}
+
+pub fn revealed_exit(map: &Map) -> Option<Point> {
+    (0..map.tiles.len())
+        .find(|idx| map.tiles[*idx] == TileType::Exit && map.revealed_tiles[*idx])
+        .map(|idx| map.index_to_point2d(idx))
+}
+
+#[test]
+fn travel_only_crosses_revealed_tiles() {
+    let mut map = Map::new(5, 3);
+    map.revealed_tiles.iter_mut().for_each(|t| *t = true);
+    let idx = map.point2d_to_index(Point::new(2, 1));
+    map.tiles[idx] = TileType::Wall;
+    let idx = map.point2d_to_index(Point::new(2, 0));
+    map.revealed_tiles[idx] = false;
+    let step = next_travel_step(&map, Point::new(1, 1), Point::new(3, 1), &HashMap::new());
+    assert_eq!(step, Some(Point::new(1, 2)));
+    let idx = map.point2d_to_index(Point::new(2, 2));
+    map.revealed_tiles[idx] = false;
+    assert_eq!(
+        next_travel_step(&map, Point::new(1, 1), Point::new(3, 1), &HashMap::new()),
+        None
+    );
+}
+
+#[test]
+fn exploring_heads_for_the_nearest_unrevealed_tile() {
+    let mut map = Map::new(7, 3);
+    (0..3).for_each(|x| {
+        (0..3).for_each(|y| {
+            let idx = map.point2d_to_index(Point::new(x, y));
+            map.revealed_tiles[idx] = true;
+        })
+    });
+    assert_eq!(
+        next_explore_step(&map, Point::new(0, 1), &HashMap::new(), &HashSet::new()),
+        Some(Point::new(1, 1))
+    );
+    map.revealed_tiles.iter_mut().for_each(|t| *t = true);
+    assert_eq!(
+        next_explore_step(&map, Point::new(0, 1), &HashMap::new(), &HashSet::new()),
+        None
+    );
+}
+
+#[test]
+fn travel_steps_around_allies_and_through_them_in_corridors() {
+    let mut map = Map::new(5, 3);
+    map.revealed_tiles.iter_mut().for_each(|t| *t = true);
+    let mut bystanders = HashMap::new();
+    bystanders.insert(map.point2d_to_index(Point::new(1, 1)), Reaction::Allied);
+    assert_ne!(
+        next_travel_step(&map, Point::new(0, 1), Point::new(4, 1), &bystanders),
+        Some(Point::new(1, 1))
+    );
+    let idx = map.point2d_to_index(Point::new(1, 0));
+    map.tiles[idx] = TileType::Wall;
+    let idx = map.point2d_to_index(Point::new(1, 2));
+    map.tiles[idx] = TileType::Wall;
+    assert_eq!(
+        next_travel_step(&map, Point::new(0, 1), Point::new(4, 1), &bystanders),
+        Some(Point::new(1, 1))
+    );
+    bystanders.insert(map.point2d_to_index(Point::new(1, 1)), Reaction::Neutral);
+    assert_eq!(
+        next_travel_step(&map, Point::new(0, 1), Point::new(4, 1), &bystanders),
+        None
+    );
+}
+
+#[test]
+fn exploring_gives_up_on_frontiers_it_has_already_visited() {
+    let mut map = Map::new(7, 3);
+    (0..3).for_each(|x| {
+        (0..3).for_each(|y| {
+            let idx = map.point2d_to_index(Point::new(x, y));
+            map.revealed_tiles[idx] = true;
+        })
+    });
+    let visited: HashSet<usize> = (0..3)
+        .map(|y| map.point2d_to_index(Point::new(2, y)))
+        .collect();
+    assert_eq!(
+        next_explore_step(&map, Point::new(0, 1), &HashMap::new(), &visited),
+        None
+    );
+}
@@ -6,4 +6,10 @@ pub enum TurnState {
     GameOver,
     Victory,
     NextLevel,
+    Inventory,
+    Trade,
+    Help,
+    Look,
+    MapOverview,
+    CharacterSheet,
 }